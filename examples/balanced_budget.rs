@@ -0,0 +1,64 @@
+//! Compares charging rewards by lack of money against a balanced budget.
+
+extern crate piston_mix_economy;
+
+use piston_mix_economy::{Budget, ConvergingAverage, Metric, Simulation, SimulationSettings};
+
+pub struct Settings {
+    tax: f64,
+    start_fortune: f64,
+    simulation: SimulationSettings,
+}
+
+fn main() {
+    for &tax in &[0.01, 0.05, 0.1, 0.2] {
+        simulate(tmp_settings(tax));
+    }
+}
+
+fn tmp_settings(tax: f64) -> Settings {
+    Settings {
+        tax,
+        start_fortune: 0.25,
+        simulation: SimulationSettings {
+            players: 100,
+            avg_trans: 0.03,
+            trans: 1000,
+            periods: 10,
+            smooth_fact: 0.99,
+            max_measurements: 100_000,
+        },
+    }
+}
+
+fn simulate(settings: Settings) {
+    let smooth_fact = settings.simulation.smooth_fact;
+
+    // Both simulations use the same seed to make the same transactions.
+    let seed = 0;
+    let mut simulation = Simulation::new(
+        settings.tax, settings.start_fortune, settings.simulation, seed);
+    let mut simulation2 = simulation.clone();
+    simulation2.economy.budget = Budget::Balanced;
+
+    let mut gini = ConvergingAverage::new(smooth_fact);
+    let mut gini2 = ConvergingAverage::new(smooth_fact);
+    let mut supply = ConvergingAverage::new(smooth_fact);
+    let mut supply2 = ConvergingAverage::new(smooth_fact);
+
+    while !gini.converged() {
+        for _ in 0..settings.simulation.periods {
+            simulation.step();
+            simulation2.step();
+        }
+
+        gini.add(simulation.economy.gini());
+        gini2.add(simulation2.economy.gini());
+        supply.add(simulation.economy.metric(Metric::TotalSupply));
+        supply2.add(simulation2.economy.metric(Metric::TotalSupply));
+    }
+
+    println!("tax: {}", settings.tax);
+    println!("gini \tcharge by lack: {} \tbalanced: {}", gini.value(), gini2.value());
+    println!("supply \tcharge by lack: {} \tbalanced: {}", supply.value(), supply2.value());
+}
@@ -7,7 +7,7 @@ extern crate timer_controller;
 
 use rand::Rng;
 use piston_window::*;
-use piston_mix_economy::Economy;
+use piston_mix_economy::{ConvergingAverage, Economy, PlayerId};
 use timer_controller::Timer;
 
 pub struct Settings {
@@ -58,13 +58,14 @@ fn simulate(settings: Settings) {
 
     let mut economy = Economy::new(tax_1, start_fortune, players);
     let mut economy2 = Economy::new(tax_2, start_fortune, players);
+    // Both economies hand out the same ids.
+    let ids: Vec<PlayerId> = economy.ids().collect();
     let update_interval = 0.1;
     let mut timer = Timer::new(update_interval);
     let mut gini_timer = Timer::new(update_interval * periods as f64);
-    let mut smooth_gini = 0.0;
-    let mut smooth_gini2 = 0.0;
-    let mut smooth_tax = 0.0;
-    let mut smooth = 1.0;
+    let mut smooth_gini = ConvergingAverage::new(smooth_fact);
+    let mut smooth_gini2 = ConvergingAverage::new(smooth_fact);
+    let mut smooth_tax = ConvergingAverage::new(smooth_fact);
 
     let mut rng = rand::rngs::OsRng;
 
@@ -83,9 +84,8 @@ fn simulate(settings: Settings) {
         timer.event(&e, || {
             // Make random transactions.
             for _ in 0..transactions {
-                let len = economy.players.len();
-                let from = rng.gen_range(0..len);
-                let to = rng.gen_range(0..len);
+                let from = ids[rng.gen_range(0..ids.len())];
+                let to = ids[rng.gen_range(0..ids.len())];
                 let _ = economy.transaction(from, to, avg_transaction);
                 let _ = economy2.transaction(from, to, avg_transaction);
             }
@@ -95,25 +95,24 @@ fn simulate(settings: Settings) {
                 settings.smooth_target,
                 settings.min_tax_1
             );
-            economy.players.sort_by(|a, b| a.partial_cmp(b).unwrap());
             economy2.update();
-            economy2.players.sort_by(|a, b| a.partial_cmp(b).unwrap());
         });
         gini_timer.event(&e, || {
-            smooth_gini += (economy.gini() - smooth_gini) * smooth;
-            smooth_gini2 += (economy2.gini() - smooth_gini2) * smooth;
-            smooth_tax += (economy.tax - smooth_tax) * smooth;
-            println!("gini \ttop: {} \tbottom: {}", smooth_gini, smooth_gini2);
-            println!("tax \ttop: {} \tsmooth: {}", smooth_tax, smooth);
-            smooth *= smooth_fact;
+            smooth_gini.add(economy.gini());
+            smooth_gini2.add(economy2.gini());
+            smooth_tax.add(economy.tax);
+            println!("gini \ttop: {} \tbottom: {}", smooth_gini.value(), smooth_gini2.value());
+            println!("tax \ttop: {} \tsmooth: {}", smooth_tax.value(), smooth_tax.weight());
         })
     }
 }
 
 fn draw_economy<G: Graphics>(economy: &Economy, color: [f32; 4], c: &Context, g: &mut G) {
     let c = &c.trans(0.0, 160.0).scale(1.0, -1.0);
-    let w: f64 = 1000.0 / economy.players.len() as f64;
-    for (i, p) in economy.players.iter().enumerate() {
+    let w: f64 = 1000.0 / economy.len() as f64;
+    let mut fortunes = economy.fortunes().to_vec();
+    fortunes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    for (i, p) in fortunes.iter().enumerate() {
         let rect = [i as f64 * w, *p * 100.0 - 2.0, w, 2.0];
         rectangle(color, rect, c.transform, g);
     }
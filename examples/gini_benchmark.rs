@@ -0,0 +1,40 @@
+//! Compares the time of computing Gini by sorting against all pairs.
+//!
+//! Run with `cargo run --release --example gini_benchmark`.
+
+extern crate piston_mix_economy;
+extern crate rand;
+
+use rand::Rng;
+use piston_mix_economy::Economy;
+use std::time::Instant;
+
+fn main() {
+    let mut rng = rand::rngs::OsRng;
+    for &players in &[100, 1_000, 10_000, 100_000] {
+        let mut economy = Economy::new(0.1, 0.25, players);
+        let ids: Vec<_> = economy.ids().collect();
+        for _ in 0..players * 10 {
+            let from = ids[rng.gen_range(0..players)];
+            let to = ids[rng.gen_range(0..players)];
+            let _ = economy.transaction(from, to, 0.03);
+        }
+
+        let start = Instant::now();
+        let gini = economy.gini();
+        let sorted = start.elapsed();
+
+        // The quadratic version takes too long for large economies.
+        if players <= 10_000 {
+            let start = Instant::now();
+            let reference = economy.gini_quadratic();
+            let quadratic = start.elapsed();
+            println!("players: {} \tgini: {} \tsorted: {:?} \tquadratic: {:?} \tspeedup: {:.1}x",
+                players, gini, sorted, quadratic,
+                quadratic.as_secs_f64() / sorted.as_secs_f64());
+            assert!((gini - reference).abs() < 1e-9);
+        } else {
+            println!("players: {} \tgini: {} \tsorted: {:?}", players, gini, sorted);
+        }
+    }
+}
@@ -2,12 +2,10 @@
 
 extern crate piston_mix_economy;
 extern crate piston_window;
-extern crate rand;
 extern crate timer_controller;
 
-use rand::Rng;
 use piston_window::*;
-use piston_mix_economy::Economy;
+use piston_mix_economy::{ConvergingAverage, Economy, Simulation, SimulationSettings};
 use timer_controller::Timer;
 
 pub struct Settings {
@@ -108,7 +106,7 @@ fn main() {
 
 fn tmp_settings(id: u32, tax: f64) -> Settings {
     Settings {
-        id: id,
+        id,
         tax_1: tax,
         tax_2: 0.0,
         start_fortune: 0.2,
@@ -121,25 +119,29 @@ fn tmp_settings(id: u32, tax: f64) -> Settings {
 }
 
 fn simulate(settings: Settings) {
-    let tax_1 = settings.tax_1;
-    let tax_2 = settings.tax_2;
-    let start_fortune = settings.start_fortune;
     let players = settings.players;
-    let avg_transaction = settings.avg_trans;
-    let transactions = settings.trans;
     let periods = settings.periods;
     let smooth_fact = settings.smooth_fact;
+    let simulation_settings = SimulationSettings {
+        players,
+        avg_trans: settings.avg_trans,
+        trans: settings.trans,
+        periods,
+        smooth_fact,
+        max_measurements: 100_000,
+    };
 
-    let mut economy = Economy::new(tax_1, start_fortune, players);
-    let mut economy2 = Economy::new(tax_2, start_fortune, players);
+    // Both simulations use the same seed to make the same transactions.
+    let seed = settings.id as u64;
+    let mut simulation = Simulation::new(
+        settings.tax_1, settings.start_fortune, simulation_settings, seed);
+    let mut simulation2 = Simulation::new(
+        settings.tax_2, settings.start_fortune, simulation_settings, seed);
     let update_interval = 0.1;
     let mut timer = Timer::new(update_interval);
     let mut gini_timer = Timer::new(update_interval * periods as f64);
-    let mut smooth_gini = 0.0;
-    let mut smooth_gini2 = 0.0;
-    let mut smooth = 1.0;
-
-    let mut rng = rand::rngs::OsRng;
+    let mut smooth_gini = ConvergingAverage::new(smooth_fact);
+    let mut smooth_gini2 = ConvergingAverage::new(smooth_fact);
 
     let window = false;
     if window {
@@ -152,66 +154,38 @@ fn simulate(settings: Settings) {
         while let Some(e) = window.next() {
             window.draw_2d(&e, |c, g, _| {
                 clear([1.0, 1.0, 1.0, 1.0], g);
-                draw_economy(&economy, &c, g, players);
+                draw_economy(&simulation.economy, &c, g, players);
                 let d = c.trans(0.0, 500.0);
-                draw_economy(&economy2, &d, g, players);
+                draw_economy(&simulation2.economy, &d, g, players);
             });
             timer.event(&e, || {
-                // Make random transactions.
-                for _ in 0..transactions {
-                    let len = economy.players.len();
-                    let from = rng.gen_range(0..len);
-                    let to = rng.gen_range(0..len);
-                    let _ = economy.transaction(from, to, avg_transaction);
-                    let _ = economy2.transaction(from, to, avg_transaction);
-                }
-
-                economy.update();
-                economy.players.sort_by(|a, b| a.partial_cmp(b).unwrap());
-                economy2.update();
-                economy2.players.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                simulation.step();
+                simulation2.step();
             });
             gini_timer.event(&e, || {
-                smooth_gini += (economy.gini() - smooth_gini) * smooth;
-                smooth_gini2 += (economy2.gini() - smooth_gini2) * smooth;
-                println!("gini \ttop: {} \tbottom: {} \tsmooth: {}", smooth_gini, smooth_gini2, smooth);
-                smooth *= smooth_fact;
+                smooth_gini.add(simulation.economy.gini());
+                smooth_gini2.add(simulation2.economy.gini());
+                println!("gini \ttop: {} \tbottom: {} \tsmooth: {}",
+                    smooth_gini.value(), smooth_gini2.value(), smooth_gini.weight());
             })
         }
     } else {
-        loop {
-            for _ in 0..periods {
-                // Make random transactions.
-                for _ in 0..transactions {
-                    let len = economy.players.len();
-                    let from = rng.gen_range(0..len);
-                    let to = rng.gen_range(0..len);
-                    let _ = economy.transaction(from, to, avg_transaction);
-                    let _ = economy2.transaction(from, to, avg_transaction);
-                }
-
-                economy.update();
-                // economy.players.sort_by(|a, b| a.partial_cmp(b).unwrap());
-                economy2.update();
-                // economy2.players.sort_by(|a, b| a.partial_cmp(b).unwrap());
-            }
-
-            smooth_gini += (economy.gini() - smooth_gini) * smooth;
-            smooth_gini2 += (economy2.gini() - smooth_gini2) * smooth;
-            smooth *= smooth_fact;
-            if smooth < 1.0 - smooth_fact { break; }
-        }
+        let summary = simulation.run();
+        let summary2 = simulation2.run();
 
         println!("id {}", settings.id);
         println!("tax 1: {}\ttax 2: {}", settings.tax_1, settings.tax_2);
-        println!("gini \ttop: {} \tbottom: {} \tsmooth: {}", smooth_gini, smooth_gini2, smooth);
+        println!("gini \ttop: {} \tbottom: {} \tsmooth: {}",
+            summary.gini, summary2.gini, summary.smooth);
     }
 }
 
 fn draw_economy<G: Graphics>(economy: &Economy, c: &Context, g: &mut G, players: usize) {
     let color = [1.0, 0.0, 0.0, 1.0];
     let w: f64 = 1000.0 / players as f64;
-    for (i, p) in economy.players.iter().enumerate() {
+    let mut fortunes = economy.fortunes().to_vec();
+    fortunes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    for (i, p) in fortunes.iter().enumerate() {
         let rect = [i as f64 * w, 0.0, w, *p * 100.0];
         rectangle(color, rect, c.transform, g);
     }
@@ -0,0 +1,25 @@
+//! Runs every record in a data file tagged with `needs_rerun`,
+//! writing the new results back to the file.
+//!
+//! Usage: `cargo run --release --example rerun -- data/smooth.dyon`
+
+extern crate piston_mix_economy;
+
+use std::fs;
+
+use piston_mix_economy::{ComparisonRecord, RecordFile};
+
+fn main() {
+    let path = std::env::args().nth(1).unwrap_or_else(|| "data/smooth.dyon".to_string());
+    let source = fs::read_to_string(&path).unwrap();
+    let mut file: RecordFile<ComparisonRecord> = match source.parse() {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("{}: {}", path, err);
+            std::process::exit(1);
+        }
+    };
+    let count = file.rerun(0);
+    fs::write(&path, file.to_string()).unwrap();
+    println!("Reran {} records in {}", count, path);
+}
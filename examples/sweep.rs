@@ -0,0 +1,43 @@
+//! Sweeps target Gini coefficients of the solver across threads.
+//!
+//! Writes CSV to standard output, or JSON Lines when passed `--json`.
+
+extern crate piston_mix_economy;
+
+use std::io;
+
+use piston_mix_economy::{sweep, SimulationSettings, SweepFormat, SweepGrid, SweepSample};
+
+fn main() {
+    let format = if std::env::args().any(|arg| arg == "--json") {
+        SweepFormat::JsonLines
+    } else {
+        SweepFormat::Csv
+    };
+
+    let mut grid = SweepGrid::new(SweepSample {
+        id: 0,
+        seed: 0,
+        tax: 0.0,
+        target_gini: None,
+        smooth_target: 0.9,
+        min_tax: 0.001,
+        start_fortune: 0.25,
+        simulation: SimulationSettings {
+            players: 100,
+            avg_trans: 0.03,
+            trans: 1000,
+            periods: 10,
+            smooth_fact: 0.99,
+            max_measurements: 100_000,
+        },
+    });
+    // Includes no solver as reference.
+    let n = 20;
+    grid.target_ginis = (0..n).map(|i| Some(0.5 * (n - i) as f64 / n as f64)).collect();
+    grid.target_ginis.push(None);
+
+    let results = sweep(&grid.samples(), 0);
+    let stdout = io::stdout();
+    format.write(&mut stdout.lock(), &results).unwrap();
+}
@@ -0,0 +1,89 @@
+//! Compares the PID tax controller against the Gini solver for random transactions.
+
+extern crate piston_mix_economy;
+
+use piston_mix_economy::{
+    ConvergingAverage,
+    Simulation,
+    SimulationSettings,
+    TaxController,
+    WindowedMean,
+};
+
+pub struct Settings {
+    target_gini: f64,
+    smooth_target: f64,
+    min_tax: f64,
+    start_fortune: f64,
+    simulation: SimulationSettings,
+}
+
+fn main() {
+    for i in 1..6 {
+        simulate(tmp_settings(0.1 * i as f64));
+    }
+}
+
+fn tmp_settings(target_gini: f64) -> Settings {
+    Settings {
+        target_gini,
+        smooth_target: 0.9,
+        min_tax: 0.001,
+        start_fortune: 0.25,
+        simulation: SimulationSettings {
+            players: 100,
+            avg_trans: 0.03,
+            trans: 1000,
+            periods: 10,
+            smooth_fact: 0.99,
+            max_measurements: 100_000,
+        },
+    }
+}
+
+fn simulate(settings: Settings) {
+    let smooth_fact = settings.simulation.smooth_fact;
+
+    // Both simulations use the same seed to make the same transactions.
+    let seed = 0;
+    let mut simulation = Simulation::new(0.0, settings.start_fortune, settings.simulation, seed);
+    let mut simulation2 = simulation.clone();
+    let mut controller = TaxController::new(settings.target_gini);
+    controller.min_tax = settings.min_tax;
+
+    let mut gini = ConvergingAverage::new(smooth_fact);
+    let mut gini2 = ConvergingAverage::new(smooth_fact);
+    let mut tax = ConvergingAverage::new(smooth_fact);
+    let mut tax2 = ConvergingAverage::new(smooth_fact);
+    // Absolute change of tax per update over the last updates, telling how jumpy it is.
+    let mut jump = WindowedMean::new(1000);
+    let mut jump2 = WindowedMean::new(1000);
+
+    while !gini.converged() {
+        for _ in 0..settings.simulation.periods {
+            let (old_tax, old_tax2) = (simulation.economy.tax, simulation2.economy.tax);
+            simulation.step_with(|economy| {
+                economy.solve(settings.target_gini, settings.smooth_target, settings.min_tax).update
+            });
+            simulation2.step_with(|economy| {
+                let report = economy.update();
+                economy.tax = controller.next_tax(economy.gini(), economy.tax);
+                report
+            });
+            jump.add((simulation.economy.tax - old_tax).abs());
+            jump2.add((simulation2.economy.tax - old_tax2).abs());
+        }
+
+        gini.add(simulation.economy.gini());
+        gini2.add(simulation2.economy.gini());
+        tax.add(simulation.economy.tax);
+        tax2.add(simulation2.economy.tax);
+    }
+
+    println!("target_gini: {}", settings.target_gini);
+    println!("gini \tsolver: {} \tcontroller: {}", gini.value(), gini2.value());
+    println!("tax \tsolver: {} \tcontroller: {}", tax.value(), tax2.value());
+    println!("jump \tsolver: {} ± {} \tcontroller: {} ± {}",
+        jump.mean().unwrap(), jump.standard_error().unwrap(),
+        jump2.mean().unwrap(), jump2.standard_error().unwrap());
+}
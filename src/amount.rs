@@ -0,0 +1,138 @@
+//! Amounts of currency.
+
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+/// An amount of currency stored in an economy.
+///
+/// Regulation is computed in `f64` and converted back to the amount type.
+/// Discrete amounts, such as integers, are rounded using a `Rounding` policy.
+///
+/// A fixed-point currency is an integer counting the smallest unit,
+/// for example cents, with the soft limit set accordingly.
+pub trait Amount:
+    Copy + PartialOrd + Debug
+    + Add<Output = Self> + Sub<Output = Self>
+    + AddAssign + SubAssign
+{
+    /// Returns the amount zero.
+    fn zero() -> Self;
+    /// Converts to `f64`.
+    fn to_f64(self) -> f64;
+    /// Converts from `f64`.
+    ///
+    /// Discrete amounts are rounded to nearest.
+    fn from_f64(value: f64) -> Self;
+    /// Returns `true` if amounts are whole units that must be rounded.
+    fn is_discrete() -> bool;
+    /// Adds two amounts, returning `None` on overflow.
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! float_amount {
+    ($t:ty) => {
+        impl Amount for $t {
+            fn zero() -> Self { 0.0 }
+            fn to_f64(self) -> f64 { self as f64 }
+            fn from_f64(value: f64) -> Self { value as $t }
+            fn is_discrete() -> bool { false }
+            fn checked_add(self, other: Self) -> Option<Self> { Some(self + other) }
+        }
+    }
+}
+
+macro_rules! integer_amount {
+    ($t:ty) => {
+        impl Amount for $t {
+            fn zero() -> Self { 0 }
+            fn to_f64(self) -> f64 { self as f64 }
+            fn from_f64(value: f64) -> Self { value.round() as $t }
+            fn is_discrete() -> bool { true }
+            fn checked_add(self, other: Self) -> Option<Self> { <$t>::checked_add(self, other) }
+        }
+    }
+}
+
+float_amount!(f64);
+float_amount!(f32);
+integer_amount!(i32);
+integer_amount!(i64);
+integer_amount!(u32);
+integer_amount!(u64);
+
+/// Tells how to round the total burn or reward of discrete amounts.
+///
+/// What is lost by rounding is carried to the next update,
+/// such that no money appears or vanishes over time.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Rounding {
+    /// Rounds down, carrying the fraction.
+    Floor,
+    /// Rounds to nearest, carrying the difference.
+    Nearest,
+    /// Rounds up, carrying the difference as debt.
+    Ceil,
+}
+
+impl Rounding {
+    fn round(self, value: f64) -> f64 {
+        match self {
+            Rounding::Floor => value.floor(),
+            Rounding::Nearest => value.round(),
+            Rounding::Ceil => value.ceil(),
+        }
+    }
+}
+
+/// Converts exact amounts to the amount type.
+///
+/// Discrete amounts are split into whole units using `allocate`.
+pub(crate) fn round_amounts<A: Amount>(
+    exact: &[f64],
+    carry: &mut f64,
+    rounding: Rounding,
+) -> Vec<A> {
+    if A::is_discrete() {
+        allocate(exact, carry, rounding).into_iter().map(A::from_f64).collect()
+    } else {
+        exact.iter().map(|&x| A::from_f64(x)).collect()
+    }
+}
+
+/// Splits exact amounts into whole units.
+///
+/// The total is rounded with the carry from previous updates,
+/// and the carry is updated with what was lost by rounding.
+/// Units are first given by rounding down each amount,
+/// then by largest fraction, breaking ties by index.
+pub(crate) fn allocate(exact: &[f64], carry: &mut f64, rounding: Rounding) -> Vec<f64> {
+    if exact.is_empty() { return vec![]; }
+    let sum: f64 = exact.iter().sum();
+    let total = rounding.round(sum + *carry);
+    *carry += sum - total;
+    let mut units: Vec<f64> = exact.iter().map(|x| x.floor()).collect();
+    let rest = total - units.iter().sum::<f64>();
+    if rest == 0.0 { return units; }
+
+    let mut order: Vec<usize> = (0..exact.len()).collect();
+    order.sort_by(|&a, &b| {
+        let fa = exact[a] - units[a];
+        let fb = exact[b] - units[b];
+        fb.partial_cmp(&fa).unwrap_or(Ordering::Equal).then(a.cmp(&b))
+    });
+    // The rest is at most one unit more than the number of amounts.
+    if rest > 0.0 {
+        for k in 0..rest as usize {
+            units[order[k % order.len()]] += 1.0;
+        }
+    } else {
+        // Take units back from smallest fractions first.
+        for k in 0..-rest as usize {
+            units[order[order.len() - 1 - k % order.len()]] -= 1.0;
+        }
+    }
+    units
+}
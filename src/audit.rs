@@ -0,0 +1,44 @@
+//! Explanations of how an update changed each player.
+
+use PlayerId;
+
+/// Records how the last update changed the fortune of a player.
+///
+/// Amounts are in currency.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Audit<A = f64> {
+    /// The player.
+    pub player: PlayerId,
+    /// The tax applied.
+    pub tax: f64,
+    /// The fortune before the update.
+    pub before: A,
+    /// The fortune after the update.
+    pub after: A,
+    /// Whether the player was at or above the soft limit, and was burned.
+    pub burned: bool,
+    /// The money burned.
+    pub burn: A,
+    /// Whether the player was rewarded.
+    ///
+    /// These are the players below the soft limit after burning,
+    /// or every player when `Budget::Balanced` shares what was burned
+    /// because no player was below the soft limit.
+    pub rewarded: bool,
+    /// The money rewarded.
+    pub reward: A,
+    /// The reward weight, which is zero for players not rewarded.
+    ///
+    /// With `SqrtReward`, this is the square root of the normalized fortune,
+    /// or of the start fortune for players below it.
+    pub weight: f64,
+    /// The normalized fortune after burning, which the weight is computed from.
+    pub weighted_fortune: f64,
+    /// Whether the player was rewarded with a fortune below the start fortune,
+    /// such that the built-in weightings used the start fortune instead.
+    ///
+    /// With `SqrtReward`, the weight is then the square root of the start fortune
+    /// instead of the square root of `weighted_fortune`.
+    pub floored: bool,
+}
@@ -0,0 +1,127 @@
+//! Estimators for measurements that fluctuate over time.
+
+use std::collections::VecDeque;
+
+/// Averages measurements with a weight that decreases for each measurement.
+///
+/// The first measurement gets weight 1,
+/// and the weight is multiplied by the smooth factor after each measurement.
+/// Early measurements are forgotten quickly while the economy settles,
+/// and later measurements refine the average until it converges.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ConvergingAverage {
+    /// How fast the weight of new measurements decreases.
+    ///
+    /// Should be in the range `[0, 1)`.
+    pub smooth_fact: f64,
+    value: f64,
+    weight: f64,
+    measurements: u64,
+}
+
+impl ConvergingAverage {
+    /// Creates a new average.
+    pub fn new(smooth_fact: f64) -> ConvergingAverage {
+        ConvergingAverage {
+            smooth_fact,
+            value: 0.0,
+            weight: 1.0,
+            measurements: 0,
+        }
+    }
+
+    /// Adds a measurement, returning the new average.
+    pub fn add(&mut self, value: f64) -> f64 {
+        self.value += (value - self.value) * self.weight;
+        self.weight *= self.smooth_fact;
+        self.measurements += 1;
+        self.value
+    }
+
+    /// Returns the average.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Returns the weight of the next measurement.
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    /// Returns the number of measurements.
+    pub fn measurements(&self) -> u64 {
+        self.measurements
+    }
+
+    /// Returns `true` when the weight has dropped below `1 - smooth_fact`.
+    pub fn converged(&self) -> bool {
+        self.weight < 1.0 - self.smooth_fact
+    }
+}
+
+/// Averages the last measurements within a window of fixed size.
+///
+/// Unlike `ConvergingAverage`, this gives equal weight to recent measurements
+/// and tells how uncertain the mean is.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowedMean {
+    size: usize,
+    values: VecDeque<f64>,
+}
+
+impl WindowedMean {
+    /// Creates a new mean over a window of some size.
+    pub fn new(size: usize) -> WindowedMean {
+        WindowedMean {
+            size,
+            values: VecDeque::with_capacity(size),
+        }
+    }
+
+    /// Adds a measurement, dropping the oldest when the window is full.
+    pub fn add(&mut self, value: f64) {
+        if self.size == 0 { return; }
+        if self.values.len() == self.size {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    /// Returns the number of measurements in the window.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if there are no measurements.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns `true` when the window is full.
+    pub fn is_full(&self) -> bool {
+        self.values.len() == self.size
+    }
+
+    /// Returns the mean of the measurements in the window.
+    ///
+    /// Returns `None` if there are no measurements.
+    pub fn mean(&self) -> Option<f64> {
+        if self.values.is_empty() { return None; }
+        Some(self.values.iter().sum::<f64>() / self.values.len() as f64)
+    }
+
+    /// Returns the standard error of the mean,
+    /// which is the sample standard deviation divided by the square root of the count.
+    ///
+    /// Measurements of an economy are correlated over time,
+    /// so this underestimates the error unless measurements are far apart.
+    /// Returns `None` if there are less than two measurements.
+    pub fn standard_error(&self) -> Option<f64> {
+        let n = self.values.len();
+        if n < 2 { return None; }
+        let mean = self.mean()?;
+        let variance = self.values.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>()
+            / (n - 1) as f64;
+        Some((variance / n as f64).sqrt())
+    }
+}
@@ -0,0 +1,77 @@
+//! Runs a scenario headless and writes a time series and summary statistics.
+//!
+//! Usage: `mix-economy <scenario.toml|scenario.json> [--out <dir>]`
+//!
+//! Writes `<name>.series.csv` and `<name>.summary.json` to the output directory,
+//! which is the current directory by default.
+
+extern crate piston_mix_economy;
+extern crate serde_json;
+extern crate toml;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use piston_mix_economy::Scenario;
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn usage() -> String {
+    "usage: mix-economy <scenario.toml|scenario.json> [--out <dir>]".into()
+}
+
+fn run() -> Result<(), String> {
+    let mut path = None;
+    let mut out = PathBuf::from(".");
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match &*arg {
+            "--out" => out = args.next().ok_or_else(usage)?.into(),
+            "-h" | "--help" => {
+                println!("{}", usage());
+                return Ok(());
+            }
+            _ if path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => return Err(usage()),
+        }
+    }
+    let path = path.ok_or_else(usage)?;
+    let scenario = read_scenario(&path)?;
+    let output = scenario.run();
+
+    let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("scenario");
+    fs::create_dir_all(&out).map_err(|err| format!("{}: {}", out.display(), err))?;
+    let series = out.join(format!("{}.series.csv", name));
+    let mut w = BufWriter::new(create(&series)?);
+    output.write_series(&mut w).and_then(|_| w.flush())
+        .map_err(|err| format!("{}: {}", series.display(), err))?;
+    let summary = out.join(format!("{}.summary.json", name));
+    serde_json::to_writer_pretty(create(&summary)?, &output.summary)
+        .map_err(|err| format!("{}: {}", summary.display(), err))?;
+
+    println!("{}", serde_json::to_string_pretty(&output.summary).unwrap());
+    Ok(())
+}
+
+fn create(path: &Path) -> Result<File, String> {
+    File::create(path).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+/// Reads a scenario as JSON if the extension is `json`, otherwise as TOML.
+fn read_scenario(path: &Path) -> Result<Scenario, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    let json = path.extension().map(|ext| ext == "json").unwrap_or(false);
+    if json {
+        serde_json::from_str(&text).map_err(|err| format!("{}: {}", path.display(), err))
+    } else {
+        toml::from_str(&text).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+}
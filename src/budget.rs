@@ -0,0 +1,21 @@
+//! How rewards are funded.
+
+/// Tells where the money distributed to players below the soft limit comes from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Budget {
+    /// Charges rewards by the total lack of money below the soft limit,
+    /// multiplied with the tax.
+    ///
+    /// This is independent of how much was burned,
+    /// such that each update can create or remove money.
+    /// This is the default.
+    ChargeByLack,
+    /// Distributes exactly what was burned, using the same weights.
+    ///
+    /// The money supply is conserved.
+    /// If no player is below the soft limit after burning,
+    /// the burned money is shared among all players.
+    Balanced,
+}
@@ -0,0 +1,122 @@
+//! Burn curves for fortunes above the soft limit.
+
+use std::sync::Arc;
+
+/// Computes how much money to burn from a fortune above the soft limit.
+///
+/// The burn is multiplied with the tax before it is removed from the fortune.
+/// A curve should return `0` for no excess and grow with the excess,
+/// such that richer players lose more money.
+pub trait BurnCurve {
+    /// Returns the burn for a normalized fortune `excess` above the soft limit.
+    ///
+    /// The `excess` is never negative.
+    fn burn(&self, excess: f64) -> f64;
+
+    /// Returns the configuration of a built-in curve, used for saving the economy.
+    ///
+    /// Returns `None` by default, for custom curves.
+    fn config(&self) -> Option<BurnConfig> { None }
+}
+
+/// Describes one of the built-in burn curves.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum BurnConfig {
+    /// Uses `SqrtBurn`.
+    Sqrt,
+    /// Uses `LinearBurn`.
+    Linear,
+    /// Uses `LogBurn`.
+    Log,
+    /// Uses `BracketBurn` with thresholds and marginal rates.
+    Bracket(Vec<(f64, f64)>),
+}
+
+impl BurnConfig {
+    /// Creates the burn curve.
+    pub fn curve(&self) -> Arc<dyn BurnCurve + Send + Sync> {
+        match *self {
+            BurnConfig::Sqrt => Arc::new(SqrtBurn),
+            BurnConfig::Linear => Arc::new(LinearBurn),
+            BurnConfig::Log => Arc::new(LogBurn),
+            BurnConfig::Bracket(ref brackets) =>
+                Arc::new(BracketBurn {brackets: brackets.clone()}),
+        }
+    }
+}
+
+/// Burns the square root of the excess.
+///
+/// This is the default curve.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SqrtBurn;
+
+impl BurnCurve for SqrtBurn {
+    fn burn(&self, excess: f64) -> f64 {
+        excess.sqrt()
+    }
+
+    fn config(&self) -> Option<BurnConfig> { Some(BurnConfig::Sqrt) }
+}
+
+/// Burns a fixed fraction of the excess.
+///
+/// With tax in range `[0, 1]` a fortune never drops below the soft limit.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LinearBurn;
+
+impl BurnCurve for LinearBurn {
+    fn burn(&self, excess: f64) -> f64 {
+        excess
+    }
+
+    fn config(&self) -> Option<BurnConfig> { Some(BurnConfig::Linear) }
+}
+
+/// Burns the natural logarithm of one plus the excess.
+///
+/// Grows slower than the square root for large fortunes.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LogBurn;
+
+impl BurnCurve for LogBurn {
+    fn burn(&self, excess: f64) -> f64 {
+        excess.ln_1p()
+    }
+
+    fn config(&self) -> Option<BurnConfig> { Some(BurnConfig::Log) }
+}
+
+/// Burns using marginal rates over brackets of the excess.
+///
+/// Works like a progressive income tax: Each rate applies only to
+/// the part of the excess that lies within its bracket.
+#[derive(Clone, Debug)]
+pub struct BracketBurn {
+    /// The lower threshold of excess and the marginal rate of each bracket.
+    ///
+    /// A bracket ends at the next higher threshold, so the order does not matter.
+    /// Excess below the lowest threshold is not burned.
+    pub brackets: Vec<(f64, f64)>,
+}
+
+impl BurnCurve for BracketBurn {
+    fn burn(&self, excess: f64) -> f64 {
+        let mut sum = 0.0;
+        for &(threshold, rate) in &self.brackets {
+            if excess <= threshold { continue; }
+            let upper = self.brackets.iter()
+                .map(|&(next, _)| next)
+                .filter(|&next| next > threshold)
+                .fold(excess, f64::min);
+            sum += (upper - threshold) * rate;
+        }
+        sum
+    }
+
+    fn config(&self) -> Option<BurnConfig> {
+        Some(BurnConfig::Bracket(self.brackets.clone()))
+    }
+}
@@ -0,0 +1,87 @@
+//! Feedback control of tax across many updates.
+
+use {Amount, Economy};
+
+/// Adjusts tax each update from the measured Gini error, using a PID loop.
+///
+/// Unlike `Economy::solve`, which looks one update ahead,
+/// the controller reacts to how the Gini actually moves between updates,
+/// including the effect of transactions.
+///
+/// The tax changes at most `max_step` per update.
+/// When the tax is limited, the integral stops growing in the direction
+/// of the error, which prevents windup.
+#[derive(Clone, Debug)]
+pub struct TaxController {
+    /// The target Gini coefficient.
+    pub target_gini: f64,
+    /// The proportional gain.
+    pub kp: f64,
+    /// The integral gain.
+    pub ki: f64,
+    /// The derivative gain.
+    pub kd: f64,
+    /// The maximum change of tax per update.
+    pub max_step: f64,
+    /// The lowest tax to use.
+    pub min_tax: f64,
+    /// The highest tax to use.
+    pub max_tax: f64,
+    integral: f64,
+    last_error: Option<f64>,
+}
+
+impl TaxController {
+    /// Creates a new controller with default gains and limits.
+    pub fn new(target_gini: f64) -> TaxController {
+        TaxController {
+            target_gini,
+            kp: 0.5,
+            ki: 0.05,
+            kd: 0.0,
+            max_step: 0.01,
+            min_tax: 0.0,
+            max_tax: 1.0,
+            integral: 0.0,
+            last_error: None,
+        }
+    }
+
+    /// Returns the tax to use next, given the measured Gini and current tax.
+    pub fn next_tax(&mut self, gini: f64, tax: f64) -> f64 {
+        // Positive error means too much inequality, which needs higher tax.
+        let error = gini - self.target_gini;
+        let derivative = self.last_error.map(|last| error - last).unwrap_or(0.0);
+        self.last_error = Some(error);
+
+        let integral = self.integral + error;
+        let output = self.kp * error + self.ki * integral + self.kd * derivative;
+        let lo = self.min_tax.max(tax - self.max_step);
+        let hi = self.max_tax.min(tax + self.max_step);
+        let next = output.max(lo).min(hi);
+
+        // Anti-windup: Only integrate when not pushing further into a limit.
+        let saturated = (output > hi && error > 0.0) || (output < lo && error < 0.0);
+        if !saturated {
+            self.integral = integral;
+        }
+        next
+    }
+
+    /// Updates the economy, then measures the Gini and sets the tax for next update.
+    ///
+    /// Like `Economy::solve`, this targets the Gini right after update.
+    /// Returns the measured Gini.
+    pub fn update<A: Amount>(&mut self, economy: &mut Economy<A>) -> f64 {
+        economy.update();
+        let gini = economy.gini();
+        economy.tax = self.next_tax(gini, economy.tax);
+        gini
+    }
+
+    /// Forgets the integral and the last error.
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.last_error = None;
+    }
+}
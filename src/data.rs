@@ -0,0 +1,513 @@
+//! Experiment records stored in Dyon data files.
+//!
+//! A data file is an array of objects with comments, like `data/smooth.dyon`.
+//! Comments are kept when a file is read and written back.
+//! Comments inside an object are written on the lines above the field that follows them.
+
+use std::fmt;
+use std::str::FromStr;
+
+use {sweep, DataError, SimulationSettings, SweepSample};
+
+/// A value of a field in a data file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataValue {
+    /// A number.
+    Number(f64),
+    /// A boolean.
+    Bool(bool),
+    /// A string.
+    Text(String),
+}
+
+impl fmt::Display for DataValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DataValue::Number(x) => write!(f, "{}", x),
+            DataValue::Bool(x) => write!(f, "{}", x),
+            DataValue::Text(ref x) => {
+                write!(f, "\"")?;
+                for c in x.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        c => write!(f, "{}", c)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+        }
+    }
+}
+
+/// A record type stored as an object in a data file.
+pub trait DyonRecord: Sized {
+    /// Reads a record from the fields of an object.
+    fn from_fields(fields: &[(String, DataValue)]) -> Result<Self, String>;
+    /// Returns the fields of the object, in the order to write them.
+    fn fields(&self) -> Vec<(&'static str, DataValue)>;
+}
+
+/// A record with the comments on the lines above it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordEntry<R> {
+    /// The comments, including `//` or `/* */`.
+    pub comments: Vec<String>,
+    /// The comments inside the object, by the name of the field that follows them.
+    ///
+    /// Comments after the last field have an empty name.
+    pub field_comments: Vec<(String, Vec<String>)>,
+    /// The record.
+    pub record: R,
+}
+
+/// A data file with an array of records.
+///
+/// Use `str::parse` to read a file and `to_string` to write it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordFile<R> {
+    /// The text before the array, usually a comment describing the fields.
+    pub header: String,
+    /// The records.
+    pub records: Vec<RecordEntry<R>>,
+    /// The comments after the last record.
+    pub footer: Vec<String>,
+}
+
+impl<R: DyonRecord> FromStr for RecordFile<R> {
+    type Err = DataError;
+
+    fn from_str(source: &str) -> Result<RecordFile<R>, DataError> {
+        let mut parser = Parser {source, pos: 0, line: 1};
+        parser.comments()?;
+        let header = source[..parser.pos].to_string();
+        parser.expect('[')?;
+        let mut records = vec![];
+        loop {
+            let comments = parser.comments()?;
+            if parser.peek() == Some(']') {
+                parser.pos += 1;
+                parser.comments()?;
+                if parser.peek().is_some() {
+                    return Err(parser.error("Expected end of file"));
+                }
+                return Ok(RecordFile {header, records, footer: comments});
+            }
+            let line = parser.line;
+            let (fields, field_comments) = parser.object()?;
+            let record = R::from_fields(&fields)
+                .map_err(|message| DataError {line, message})?;
+            records.push(RecordEntry {comments, field_comments, record});
+            parser.comments()?;
+            if parser.peek() == Some(',') {
+                parser.pos += 1;
+            } else if parser.peek() != Some(']') {
+                return Err(parser.error("Expected `,` or `]`"));
+            }
+        }
+    }
+}
+
+impl<R: DyonRecord> fmt::Display for RecordFile<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}[", self.header)?;
+        for entry in &self.records {
+            for comment in &entry.comments {
+                writeln!(f, "    {}", comment)?;
+            }
+            writeln!(f, "    {{")?;
+            let fields = entry.record.fields();
+            for &(name, ref value) in &fields {
+                for (_, comments) in entry.field_comments.iter().filter(|(n, _)| n == name) {
+                    for comment in comments {
+                        writeln!(f, "        {}", comment)?;
+                    }
+                }
+                writeln!(f, "        {}: {},", name, value)?;
+            }
+            // Comments of fields that are not written go last.
+            for (name, comments) in &entry.field_comments {
+                if fields.iter().any(|&(n, _)| n == name) { continue; }
+                for comment in comments {
+                    writeln!(f, "        {}", comment)?;
+                }
+            }
+            writeln!(f, "    }},")?;
+        }
+        for comment in &self.footer {
+            writeln!(f, "    {}", comment)?;
+        }
+        writeln!(f, "]")
+    }
+}
+
+// The fields of an object and the comments by field.
+type Object = (Vec<(String, DataValue)>, Vec<(String, Vec<String>)>);
+
+struct Parser<'a> {
+    source: &'a str,
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &str) -> DataError {
+        DataError {line: self.line, message: message.to_string()}
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.source[self.pos..]
+    }
+
+    fn advance(&mut self, len: usize) {
+        self.line += self.source[self.pos..self.pos + len].matches('\n').count();
+        self.pos += len;
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), DataError> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected `{}`", c)))
+        }
+    }
+
+    /// Skips whitespace, returning the comments.
+    fn comments(&mut self) -> Result<Vec<String>, DataError> {
+        let mut comments = vec![];
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.advance(rest.len() - trimmed.len());
+            let len = if trimmed.starts_with("//") {
+                trimmed.find('\n').unwrap_or(trimmed.len())
+            } else if trimmed.starts_with("/*") {
+                match trimmed.find("*/") {
+                    Some(end) => end + 2,
+                    None => return Err(self.error("Unterminated comment")),
+                }
+            } else {
+                return Ok(comments);
+            };
+            comments.push(trimmed[..len].trim_end().to_string());
+            self.advance(len);
+        }
+    }
+
+    /// Reads an object, returning the fields and the comments by field.
+    fn object(&mut self) -> Result<Object, DataError> {
+        self.expect('{')?;
+        let mut fields = vec![];
+        let mut field_comments = vec![];
+        let mut comments = vec![];
+        loop {
+            comments.extend(self.comments()?);
+            if self.peek() == Some('}') {
+                self.pos += 1;
+                if !comments.is_empty() {
+                    field_comments.push((String::new(), comments));
+                }
+                return Ok((fields, field_comments));
+            }
+            let len = self.rest()
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(self.rest().len());
+            if len == 0 { return Err(self.error("Expected field name")); }
+            let name = self.rest()[..len].to_string();
+            self.pos += len;
+            comments.extend(self.comments()?);
+            self.expect(':')?;
+            comments.extend(self.comments()?);
+            let value = self.value()?;
+            if !comments.is_empty() {
+                field_comments.push((name.clone(), std::mem::take(&mut comments)));
+            }
+            fields.push((name, value));
+            comments.extend(self.comments()?);
+            if self.peek() == Some(',') {
+                self.pos += 1;
+            } else if self.peek() != Some('}') {
+                return Err(self.error("Expected `,` or `}`"));
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<DataValue, DataError> {
+        let rest = self.rest();
+        if rest.starts_with("true") {
+            self.pos += 4;
+            Ok(DataValue::Bool(true))
+        } else if rest.starts_with("false") {
+            self.pos += 5;
+            Ok(DataValue::Bool(false))
+        } else if rest.starts_with('"') {
+            let mut text = String::new();
+            let mut chars = rest.char_indices().skip(1);
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '"' => {
+                        self.advance(i + 1);
+                        return Ok(DataValue::Text(text));
+                    }
+                    '\\' => match chars.next() {
+                        Some((_, 'n')) => text.push('\n'),
+                        Some((_, c)) => text.push(c),
+                        None => break,
+                    },
+                    c => text.push(c),
+                }
+            }
+            Err(self.error("Unterminated string"))
+        } else {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_digit() || "+-.eE".contains(c)))
+                .unwrap_or(rest.len());
+            match rest[..len].parse() {
+                Ok(x) if len > 0 => {
+                    self.pos += len;
+                    Ok(DataValue::Number(x))
+                }
+                _ => Err(self.error("Expected value")),
+            }
+        }
+    }
+}
+
+// Reads fields of an object, checking that all fields are known.
+struct Fields<'a> {
+    fields: &'a [(String, DataValue)],
+}
+
+impl<'a> Fields<'a> {
+    fn new(fields: &'a [(String, DataValue)], known: &[&str]) -> Result<Fields<'a>, String> {
+        for (name, _) in fields {
+            if !known.contains(&&**name) {
+                return Err(format!("Unknown field `{}`", name));
+            }
+        }
+        Ok(Fields {fields})
+    }
+
+    fn get(&self, name: &str) -> Option<&'a DataValue> {
+        self.fields.iter().find(|(n, _)| n == name).map(|(_, value)| value)
+    }
+
+    fn opt_number(&self, name: &str) -> Result<Option<f64>, String> {
+        match self.get(name) {
+            Some(&DataValue::Number(x)) => Ok(Some(x)),
+            Some(_) => Err(format!("Expected number for `{}`", name)),
+            None => Ok(None),
+        }
+    }
+
+    fn number(&self, name: &str) -> Result<f64, String> {
+        self.opt_number(name)?.ok_or_else(|| format!("Missing field `{}`", name))
+    }
+
+    fn bool_or(&self, name: &str, default: bool) -> Result<bool, String> {
+        match self.get(name) {
+            Some(&DataValue::Bool(x)) => Ok(x),
+            Some(_) => Err(format!("Expected boolean for `{}`", name)),
+            None => Ok(default),
+        }
+    }
+
+    fn text(&self, name: &str) -> Result<String, String> {
+        match self.get(name) {
+            Some(DataValue::Text(x)) => Ok(x.clone()),
+            Some(_) => Err(format!("Expected string for `{}`", name)),
+            None => Err(format!("Missing field `{}`", name)),
+        }
+    }
+}
+
+/// A side by side comparison of two economies with different tax,
+/// as in `data/smooth.dyon`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComparisonRecord {
+    /// Unique id of the sample.
+    pub id: u32,
+    /// Whether the sample needs to be run again.
+    pub needs_rerun: bool,
+    /// The tax of economy 1.
+    pub tax_1: f64,
+    /// The tax of economy 2.
+    pub tax_2: f64,
+    /// The start fortune of players.
+    pub start_fortune: f64,
+    /// The number of players.
+    pub players: usize,
+    /// Deprecated relation between the tax rates.
+    pub diff_1_vs_2: Option<f64>,
+    /// The fixed transaction amount.
+    pub avg_trans: f64,
+    /// The number of transactions within each period.
+    pub trans: u32,
+    /// The number of periods between each measurement of Gini.
+    pub periods: u32,
+    /// The Gini coefficient of economy 1.
+    pub economy_1: f64,
+    /// The Gini coefficient of economy 2.
+    pub economy_2: f64,
+    /// The weight of a new measurement when the run ended.
+    pub smooth: f64,
+    /// The random generator.
+    pub rng: String,
+    /// The seed of the random generator, for reproducible runs.
+    pub seed: Option<u64>,
+    /// The rate of convergence.
+    pub smooth_fact: f64,
+}
+
+impl DyonRecord for ComparisonRecord {
+    fn from_fields(fields: &[(String, DataValue)]) -> Result<ComparisonRecord, String> {
+        let fields = Fields::new(fields, &[
+            "id", "needs_rerun", "tax_1", "tax_2", "start_fortune", "players", "diff_1_vs_2",
+            "avg_trans", "trans", "periods", "economy_1", "economy_2", "smooth", "rng",
+            "seed", "smooth_fact",
+        ])?;
+        Ok(ComparisonRecord {
+            id: fields.number("id")? as u32,
+            needs_rerun: fields.bool_or("needs_rerun", false)?,
+            tax_1: fields.number("tax_1")?,
+            tax_2: fields.number("tax_2")?,
+            start_fortune: fields.number("start_fortune")?,
+            players: fields.number("players")? as usize,
+            diff_1_vs_2: fields.opt_number("diff_1_vs_2")?,
+            avg_trans: fields.number("avg_trans")?,
+            trans: fields.number("trans")? as u32,
+            periods: fields.number("periods")? as u32,
+            economy_1: fields.number("economy_1")?,
+            economy_2: fields.number("economy_2")?,
+            smooth: fields.number("smooth")?,
+            rng: fields.text("rng")?,
+            seed: fields.opt_number("seed")?.map(|x| x as u64),
+            smooth_fact: fields.number("smooth_fact")?,
+        })
+    }
+
+    fn fields(&self) -> Vec<(&'static str, DataValue)> {
+        let mut fields = vec![("id", DataValue::Number(self.id as f64))];
+        if self.needs_rerun {
+            fields.push(("needs_rerun", DataValue::Bool(true)));
+        }
+        fields.push(("tax_1", DataValue::Number(self.tax_1)));
+        fields.push(("tax_2", DataValue::Number(self.tax_2)));
+        fields.push(("start_fortune", DataValue::Number(self.start_fortune)));
+        fields.push(("players", DataValue::Number(self.players as f64)));
+        if let Some(x) = self.diff_1_vs_2 {
+            fields.push(("diff_1_vs_2", DataValue::Number(x)));
+        }
+        fields.push(("avg_trans", DataValue::Number(self.avg_trans)));
+        fields.push(("trans", DataValue::Number(self.trans as f64)));
+        fields.push(("periods", DataValue::Number(self.periods as f64)));
+        fields.push(("economy_1", DataValue::Number(self.economy_1)));
+        fields.push(("economy_2", DataValue::Number(self.economy_2)));
+        fields.push(("smooth", DataValue::Number(self.smooth)));
+        fields.push(("rng", DataValue::Text(self.rng.clone())));
+        if let Some(x) = self.seed {
+            fields.push(("seed", DataValue::Number(x as f64)));
+        }
+        fields.push(("smooth_fact", DataValue::Number(self.smooth_fact)));
+        fields
+    }
+}
+
+impl ComparisonRecord {
+    /// Returns the samples simulating economy 1 and 2 with the same seed.
+    pub fn samples(&self, seed: u64) -> [SweepSample; 2] {
+        let sample = SweepSample {
+            id: self.id,
+            seed,
+            tax: self.tax_1,
+            target_gini: None,
+            smooth_target: 0.0,
+            min_tax: 0.0,
+            start_fortune: self.start_fortune,
+            simulation: SimulationSettings {
+                players: self.players,
+                avg_trans: self.avg_trans,
+                trans: self.trans,
+                periods: self.periods,
+                smooth_fact: self.smooth_fact,
+                max_measurements: MAX_MEASUREMENTS,
+            },
+        };
+        [sample, SweepSample {tax: self.tax_2, ..sample}]
+    }
+}
+
+// The most measurements of a rerun, far beyond what converging takes.
+const MAX_MEASUREMENTS: u64 = 1_000_000;
+
+impl RecordFile<ComparisonRecord> {
+    /// Runs every record that needs rerun across threads, updating its results.
+    ///
+    /// Each record is simulated with a seeded `Simulation`, using the id as seed,
+    /// such that the new results are reproducible.
+    /// Uses the available parallelism when `threads` is zero.
+    /// Records whose runs did not converge still need rerun afterwards.
+    /// Returns the number of records that were run.
+    pub fn rerun(&mut self, threads: usize) -> usize {
+        let mut indices = vec![];
+        let mut samples = vec![];
+        for (i, entry) in self.records.iter().enumerate() {
+            if entry.record.needs_rerun {
+                indices.push(i);
+                samples.extend_from_slice(&entry.record.samples(entry.record.id as u64));
+            }
+        }
+        let results = sweep(&samples, threads);
+        for (&i, pair) in indices.iter().zip(results.chunks(2)) {
+            let record = &mut self.records[i].record;
+            record.needs_rerun = !(pair[0].summary.converged && pair[1].summary.converged);
+            record.economy_1 = pair[0].summary.gini;
+            record.economy_2 = pair[1].summary.gini;
+            record.smooth = pair[0].summary.smooth;
+            record.rng = "split_mix64".to_string();
+            record.seed = Some(pair[0].sample.seed);
+        }
+        indices.len()
+    }
+}
+
+/// Measurements of the Gini solver for some start fortune,
+/// as in `data/solver-bug.dyon`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SolverRecord {
+    /// The name of the sample.
+    pub name: String,
+    /// The start fortune of players.
+    pub start_fortune: f64,
+    /// The Gini coefficient solved at 0.5 target.
+    pub sh: f64,
+    /// The target Gini coefficient where the solver starts converging.
+    pub cliff: f64,
+}
+
+impl DyonRecord for SolverRecord {
+    fn from_fields(fields: &[(String, DataValue)]) -> Result<SolverRecord, String> {
+        let fields = Fields::new(fields, &["name", "start_fortune", "sh", "cliff"])?;
+        Ok(SolverRecord {
+            name: fields.text("name")?,
+            start_fortune: fields.number("start_fortune")?,
+            sh: fields.number("sh")?,
+            cliff: fields.number("cliff")?,
+        })
+    }
+
+    fn fields(&self) -> Vec<(&'static str, DataValue)> {
+        vec![
+            ("name", DataValue::Text(self.name.clone())),
+            ("start_fortune", DataValue::Number(self.start_fortune)),
+            ("sh", DataValue::Number(self.sh)),
+            ("cliff", DataValue::Number(self.cliff)),
+        ]
+    }
+}
@@ -0,0 +1,109 @@
+//! Errors.
+
+use std::error::Error;
+use std::fmt;
+
+use PlayerId;
+
+/// Tells why a transaction was rejected.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TransactionError {
+    /// A player can not pay to itself.
+    SamePlayer,
+    /// The paying player does not have enough money.
+    ///
+    /// A player must have a positive fortune left after paying.
+    InsufficientFunds,
+    /// The player is not in the economy.
+    UnknownPlayer(PlayerId),
+    /// The amount is negative, infinite or NaN.
+    InvalidAmount(f64),
+    /// The receiving fortune would not fit in the amount type.
+    Overflow,
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TransactionError::SamePlayer =>
+                write!(f, "A player can not pay to itself"),
+            TransactionError::InsufficientFunds =>
+                write!(f, "Not enough money"),
+            TransactionError::UnknownPlayer(id) =>
+                write!(f, "Unknown player `{:?}`", id),
+            TransactionError::InvalidAmount(amount) =>
+                write!(f, "Invalid amount `{}`", amount),
+            TransactionError::Overflow =>
+                write!(f, "Fortune would overflow"),
+        }
+    }
+}
+
+impl Error for TransactionError {}
+
+/// Tells why replaying a ledger failed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ReplayError {
+    /// An entry does not follow the previous one.
+    Sequence {
+        /// The expected sequence number.
+        expected: u64,
+        /// The sequence number found.
+        found: u64,
+    },
+    /// A joining player did not get the same id as recorded.
+    Join {
+        /// The sequence number of the entry.
+        sequence: u64,
+        /// The recorded player.
+        player: PlayerId,
+    },
+    /// The player is not in the economy.
+    UnknownPlayer {
+        /// The sequence number of the entry.
+        sequence: u64,
+        /// The unknown player.
+        player: PlayerId,
+    },
+    /// A transaction or payment was rejected.
+    Transaction {
+        /// The sequence number of the entry.
+        sequence: u64,
+        /// Why the transaction was rejected.
+        error: TransactionError,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ReplayError::Sequence {expected, found} =>
+                write!(f, "Expected entry `{}`, found `{}`", expected, found),
+            ReplayError::Join {sequence, player} =>
+                write!(f, "Entry `{}`: Player `{:?}` did not get the same id", sequence, player),
+            ReplayError::UnknownPlayer {sequence, player} =>
+                write!(f, "Entry `{}`: Unknown player `{:?}`", sequence, player),
+            ReplayError::Transaction {sequence, error} =>
+                write!(f, "Entry `{}`: {}", sequence, error),
+        }
+    }
+}
+
+impl Error for ReplayError {}
+
+/// Tells why a data file could not be read.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataError {
+    /// The line where the error was found, starting at 1.
+    pub line: usize,
+    /// What went wrong.
+    pub message: String,
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for DataError {}
@@ -0,0 +1,210 @@
+//! Append-only journal of changes to the economy.
+
+use {Amount, Departure, Economy, PlayerId, Purpose, ReplayError};
+
+/// A change to the economy recorded in the ledger.
+///
+/// Amounts are in currency.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Event<A = f64> {
+    /// A player joined the economy with some fortune.
+    Join {
+        /// The new player.
+        player: PlayerId,
+        /// The fortune given to the player.
+        fortune: A,
+    },
+    /// A player left the economy.
+    Leave {
+        /// The player leaving.
+        player: PlayerId,
+        /// What happened to the fortune of the player.
+        departure: Departure,
+    },
+    /// Money was moved between two players.
+    Transaction {
+        /// The paying player.
+        from: PlayerId,
+        /// The receiving player.
+        to: PlayerId,
+        /// The money moved.
+        amount: A,
+    },
+    /// The tax was changed before an update.
+    TaxChange {
+        /// The new tax.
+        tax: f64,
+    },
+    /// Money was burned from a player in an update.
+    Burn {
+        /// The player burned.
+        player: PlayerId,
+        /// The money burned.
+        amount: A,
+    },
+    /// Money was rewarded to a player in an update.
+    Reward {
+        /// The player rewarded.
+        player: PlayerId,
+        /// The money rewarded.
+        amount: A,
+    },
+    /// An update finished, after its burns and rewards.
+    Update {
+        /// The money collected by the treasury.
+        collected: A,
+        /// What was lost by rounding burns, carried to next update.
+        burn_carry: f64,
+        /// What was lost by rounding rewards, carried to next update.
+        reward_carry: f64,
+    },
+    /// Money was paid from the treasury to a player.
+    TreasuryPayment {
+        /// The receiving player.
+        to: PlayerId,
+        /// The money paid.
+        amount: A,
+        /// What the money was spent on.
+        purpose: Purpose,
+    },
+    /// Money was spent from the treasury outside the economy.
+    TreasurySpend {
+        /// The money spent.
+        amount: A,
+        /// What the money was spent on.
+        purpose: Purpose,
+    },
+}
+
+/// An event with its sequence number in the ledger.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Entry<A = f64> {
+    /// The sequence number, increasing by one for each entry.
+    pub sequence: u64,
+    /// The change to the economy.
+    pub event: Event<A>,
+}
+
+/// Records changes to the economy when `Economy::record_ledger` is set.
+///
+/// Entries can be moved out with `Ledger::take`, for example to store them,
+/// while sequence numbers keep increasing.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ledger<A = f64> {
+    entries: Vec<Entry<A>>,
+    next_sequence: u64,
+    // The last tax recorded.
+    pub(crate) tax: f64,
+}
+
+impl<A> Ledger<A> {
+    /// Creates a new empty ledger, starting with some tax.
+    pub(crate) fn new(tax: f64) -> Ledger<A> {
+        Ledger {
+            entries: vec![],
+            next_sequence: 0,
+            tax,
+        }
+    }
+
+    /// Returns the entries recorded since last taken.
+    pub fn entries(&self) -> &[Entry<A>] {
+        &self.entries
+    }
+
+    /// Returns the sequence number of the next entry.
+    pub fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    /// Moves the recorded entries out of the ledger.
+    pub fn take(&mut self) -> Vec<Entry<A>> {
+        ::std::mem::take(&mut self.entries)
+    }
+
+    pub(crate) fn push(&mut self, event: Event<A>) {
+        if let Event::TaxChange {tax} = event {
+            self.tax = tax;
+        }
+        self.entries.push(Entry {sequence: self.next_sequence, event});
+        self.next_sequence += 1;
+    }
+}
+
+impl<A: Amount> Economy<A> {
+    /// Rebuilds an economy by replaying ledger entries on a copy of this snapshot.
+    ///
+    /// Entries before the next sequence number of the snapshot ledger are skipped,
+    /// such that the whole journal since recording started can be passed.
+    /// The remaining entries must follow each other without gaps.
+    /// Replayed entries are recorded in the new ledger when the snapshot records.
+    ///
+    /// Burns and rewards are replayed as recorded, not recomputed,
+    /// such that the result is exactly the same as the recorded economy.
+    pub fn replay(&self, entries: &[Entry<A>]) -> Result<Economy<A>, ReplayError> {
+        let mut economy = self.clone();
+        let start = self.ledger.next_sequence();
+        let entries = entries.iter().skip_while(|entry| entry.sequence < start);
+        for (expected, entry) in (start..).zip(entries) {
+            if entry.sequence != expected {
+                return Err(ReplayError::Sequence {expected, found: entry.sequence});
+            }
+            economy.apply(entry)?;
+            economy.record(entry.event);
+        }
+        Ok(economy)
+    }
+
+    fn apply(&mut self, entry: &Entry<A>) -> Result<(), ReplayError> {
+        let sequence = entry.sequence;
+        let unknown = |player| ReplayError::UnknownPlayer {sequence, player};
+        match entry.event {
+            Event::Join {player, fortune} => {
+                self.players.push(fortune);
+                if self.slots.insert() != player {
+                    return Err(ReplayError::Join {sequence, player});
+                }
+            }
+            Event::Leave {player, departure} => {
+                self.leave(player, departure).ok_or_else(|| unknown(player))?;
+            }
+            Event::Transaction {from, to, amount} => {
+                let (i, j) = self.transaction_indices(from, to, amount)
+                    .map_err(|error| ReplayError::Transaction {sequence, error})?;
+                self.players[i] -= amount;
+                self.players[j] += amount;
+            }
+            Event::TaxChange {tax} => self.tax = tax,
+            Event::Burn {player, amount} => {
+                let i = self.slots.get(player).ok_or_else(|| unknown(player))?;
+                self.players[i] -= amount;
+            }
+            Event::Reward {player, amount} => {
+                let i = self.slots.get(player).ok_or_else(|| unknown(player))?;
+                self.players[i] += amount;
+            }
+            Event::Update {collected, burn_carry, reward_carry} => {
+                self.treasury.collect_burn(collected);
+                self.burn_carry = burn_carry;
+                self.reward_carry = reward_carry;
+            }
+            Event::TreasuryPayment {to, amount, purpose} => {
+                let i = self.slots.get(to).ok_or_else(|| unknown(to))?;
+                self.treasury.check_withdraw(amount)
+                    .map_err(|error| ReplayError::Transaction {sequence, error})?;
+                self.treasury.withdraw(amount, purpose);
+                self.players[i] += amount;
+            }
+            Event::TreasurySpend {amount, purpose} => {
+                self.treasury.check_withdraw(amount)
+                    .map_err(|error| ReplayError::Transaction {sequence, error})?;
+                self.treasury.withdraw(amount, purpose);
+            }
+        }
+        Ok(())
+    }
+}
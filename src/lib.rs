@@ -1,15 +1,99 @@
 #![deny(missing_docs)]
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+
+pub use amount::{Amount, Rounding};
+pub use audit::Audit;
+pub use average::{ConvergingAverage, WindowedMean};
+pub use budget::Budget;
+pub use burn::{BracketBurn, BurnConfig, BurnCurve, LinearBurn, LogBurn, SqrtBurn};
+pub use controller::TaxController;
+pub use data::{
+    ComparisonRecord,
+    DataValue,
+    DyonRecord,
+    RecordEntry,
+    RecordFile,
+    SolverRecord,
+};
+pub use error::{DataError, ReplayError, TransactionError};
+pub use ledger::{Entry, Event, Ledger};
+pub use metric::Metric;
+pub use reward::{CappedReward, FlatReward, LinearReward, RewardConfig, RewardWeighting, SqrtReward};
+pub use player::{Departure, PlayerId};
+pub use projection::Projection;
+pub use report::UpdateReport;
+pub use scenario::{
+    DurationMode,
+    Regulation,
+    RegulationMode,
+    Scenario,
+    ScenarioDuration,
+    ScenarioEconomy,
+    ScenarioOutput,
+    ScenarioRow,
+    ScenarioSummary,
+    ScenarioTransactions,
+};
+pub use scratch::Scratch;
+pub use simulation::{Simulation, SimulationSettings, SimulationSummary};
+#[cfg(feature = "serde")]
+pub use snapshot::SNAPSHOT_VERSION;
+pub use treasury::{Purpose, Treasury};
+pub use sweep::{sweep, SweepFormat, SweepGrid, SweepResult, SweepSample};
+pub use solve::{BoundedSolveReport, Clamp, InflationBound, SolveReport, SolverStrategy};
+
+use std::sync::Arc;
+
+use amount::round_amounts;
+use player::Slots;
+
+mod amount;
+mod audit;
+mod average;
+mod budget;
+mod burn;
+mod controller;
+mod data;
+mod error;
+mod ledger;
+mod metric;
+mod player;
+mod projection;
+mod report;
+mod reward;
+mod scenario;
+mod scratch;
+mod simulation;
+#[cfg(feature = "serde")]
+mod snapshot;
+mod solve;
+mod stats;
+mod sweep;
+mod treasury;
+
 /// Represents the whole economy.
 ///
-/// Each player has a normalized fortune against an upper soft limit.
+/// Each player has a fortune in currency, which is normalized
+/// against an upper soft limit when regulating the economy.
 /// The difference from the upper limit is charging the economy.
+/// By default the soft limit is 1, such that currency is normalized.
+///
+/// Currency is stored as an `Amount`, which is `f64` by default.
+/// Integer currency is rounded deterministically when regulating,
+/// carrying what is lost by rounding to the next update.
 ///
 /// The tax tells how fast to burn money of fortunes above the soft limit,
 /// and how much to give each player below the soft limit per time interval.
+/// The shape of the burn is controlled by a `BurnCurve`,
+/// and the share given to each player by a `RewardWeighting`.
 ///
 /// The start fortune is given to new players.
+/// Players are referred to by a `PlayerId`, which stays valid
+/// when other players leave the economy.
 ///
 /// Call `Economy::update` at regular time intervals to distribute wealth,
 /// using a fixed tax rate. The Gini index can vary depending on economic activity.
@@ -17,154 +101,576 @@
 /// Call `Economy::solve` at regular time intervals to distribute wealth,
 /// using a target Gini coefficient.
 /// The tax is automatically adjusted to meet the target.
+/// Alternatively, use a `TaxController` to adjust the tax across many updates.
 #[derive(Clone)]
-pub struct Economy {
-    /// The fortunes of the players.
-    pub players: Vec<f64>,
-    /// The progressive tax factor, as the square root of fortune above 1.
+pub struct Economy<A = f64> {
+    // The fortunes of the players, without holes.
+    players: Vec<A>,
+    slots: Slots,
+    /// The progressive tax factor, multiplied with the burn curve.
     pub tax: f64,
-    /// The initial fortune. Should be in the range [0, 1].
-    pub start_fortune: f64,
+    /// Computes the burn from the normalized fortune above 1.
+    ///
+    /// Uses `SqrtBurn` by default.
+    pub burn_curve: Arc<dyn BurnCurve + Send + Sync>,
+    /// Computes the share of rewards for normalized fortunes below 1.
+    ///
+    /// Uses `SqrtReward` by default.
+    pub reward_weighting: Arc<dyn RewardWeighting + Send + Sync>,
+    /// The initial fortune, in currency.
+    /// Should be in the range [0, soft_limit].
+    pub start_fortune: A,
+    /// The upper soft limit of fortunes, in currency.
+    ///
+    /// Fortunes are divided by this value to get normalized units.
+    pub soft_limit: A,
+    /// Money collected by the economy, to be spent on gameplay.
+    pub treasury: Treasury<A>,
+    /// Whether money burned above the soft limit goes to the treasury.
+    ///
+    /// With `Budget::Balanced`, the burned money is paid out to players,
+    /// so nothing is left for the treasury.
+    /// Is `false` by default, such that burned money disappears.
+    pub collect_burns: bool,
+    /// Tells where the money distributed to poor players comes from.
+    ///
+    /// Uses `Budget::ChargeByLack` by default.
+    pub budget: Budget,
+    /// Tells how to round burns and rewards of discrete amounts.
+    ///
+    /// Uses `Rounding::Floor` by default.
+    pub rounding: Rounding,
+    /// Whether to record an `Audit` for each player when updating.
+    ///
+    /// Is `false` by default.
+    pub audit_updates: bool,
+    // How each player was changed by the last update, when auditing.
+    audits: Vec<Audit<A>>,
+    // The position of the audit of each player, by slot.
+    audit_positions: Vec<Option<usize>>,
+    /// Whether to record changes to the economy in the ledger.
+    ///
+    /// Is `false` by default.
+    pub record_ledger: bool,
+    ledger: Ledger<A>,
+    // What was lost by rounding burns and rewards, carried to next update.
+    burn_carry: f64,
+    reward_carry: f64,
 }
 
-impl Economy {
-    /// Creates a new economy.
-    pub fn new(tax: f64, start_fortune: f64, players: usize) -> Economy {
-        Economy {
-            players: vec![start_fortune; players],
-            tax: tax,
-            start_fortune: start_fortune,
+impl<A: Amount> Economy<A> {
+    /// Creates a new economy with a soft limit of `1`.
+    ///
+    /// This suits fortunes in normalized units, such as `f64`.
+    /// For discrete amounts the soft limit is then a single coin,
+    /// putting every player with a fortune above the limit.
+    /// Use `Economy::with_soft_limit` for discrete amounts.
+    pub fn new(tax: f64, start_fortune: A, players: usize) -> Economy<A> {
+        let mut economy = Economy {
+            players: Vec::with_capacity(players),
+            slots: Slots::default(),
+            tax,
+            burn_curve: Arc::new(SqrtBurn),
+            reward_weighting: Arc::new(SqrtReward),
+            start_fortune,
+            soft_limit: A::from_f64(1.0),
+            treasury: Treasury::new(),
+            collect_burns: false,
+            budget: Budget::ChargeByLack,
+            rounding: Rounding::Floor,
+            audit_updates: false,
+            audits: vec![],
+            audit_positions: vec![],
+            record_ledger: false,
+            ledger: Ledger::new(tax),
+            burn_carry: 0.0,
+            reward_carry: 0.0,
+        };
+        for _ in 0..players {
+            economy.add_player();
         }
+        economy
+    }
+
+    /// Creates a new economy with fortunes in currency against a soft limit.
+    pub fn with_soft_limit(
+        tax: f64,
+        start_fortune: A,
+        players: usize,
+        soft_limit: A,
+    ) -> Economy<A> {
+        let mut economy = Economy::new(tax, start_fortune, players);
+        economy.soft_limit = soft_limit;
+        economy
+    }
+
+    /// Converts an amount of currency to normalized units.
+    pub fn normalize(&self, amount: A) -> f64 {
+        amount.to_f64() / self.soft_limit.to_f64()
+    }
+
+    /// Converts normalized units to an amount of currency.
+    pub fn denormalize(&self, value: f64) -> A {
+        A::from_f64(value * self.soft_limit.to_f64())
     }
 
     /// Adds a player to the economy.
-    pub fn add_player(&mut self) -> usize {
-        self.players.push(self.start_fortune);
-        self.players.len() - 1
+    pub fn add_player(&mut self) -> PlayerId {
+        let fortune = self.start_fortune;
+        self.players.push(fortune);
+        let player = self.slots.insert();
+        self.record(Event::Join {player, fortune});
+        player
+    }
+
+    /// Removes a player from the economy.
+    ///
+    /// Returns the fortune the player had when leaving,
+    /// or `None` if the player is not in the economy.
+    pub fn remove_player(&mut self, id: PlayerId, departure: Departure)
+    -> Option<A> {
+        let fortune = self.leave(id, departure)?;
+        self.record(Event::Leave {player: id, departure});
+        Some(fortune)
+    }
+
+    fn leave(&mut self, id: PlayerId, departure: Departure) -> Option<A> {
+        let i = self.slots.remove(id)?;
+        let fortune = self.players.swap_remove(i);
+        match departure {
+            Departure::Burn => {}
+            Departure::Redistribute => {
+                let n = self.players.len() as f64;
+                let shares = vec![fortune.to_f64() / n; self.players.len()];
+                // Rounding to nearest without carry keeps the sum exact.
+                let shares: Vec<A> = round_amounts(&shares, &mut 0.0, Rounding::Nearest);
+                for (p, share) in self.players.iter_mut().zip(shares) {
+                    *p += share;
+                }
+            }
+            Departure::Treasury => self.treasury.collect_departure(fortune),
+        }
+        Some(fortune)
+    }
+
+    /// Returns `true` if the player is in the economy.
+    pub fn contains(&self, id: PlayerId) -> bool {
+        self.slots.get(id).is_some()
+    }
+
+    /// Returns the number of players.
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    /// Returns `true` if there are no players.
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    /// Returns the fortune of a player.
+    pub fn fortune(&self, id: PlayerId) -> Option<A> {
+        self.slots.get(id).map(|i| self.players[i])
     }
 
-    /// Finds the minimum and maximum fortune.
-    pub fn min_max(&self) -> (f64, f64) {
-        let mut min: Option<f64> = None;
-        let mut max: Option<f64> = None;
+    /// Returns the fortunes of all players.
+    ///
+    /// The order is the same as `Economy::ids`,
+    /// but changes when players leave.
+    pub fn fortunes(&self) -> &[A] {
+        &self.players
+    }
+
+    /// Returns the ids of all players.
+    pub fn ids(&self) -> impl Iterator<Item = PlayerId> + '_ {
+        (0..self.players.len()).map(move |i| self.slots.id_at(i))
+    }
+
+    /// Finds the minimum and maximum fortune, in currency.
+    pub fn min_max(&self) -> (A, A) {
+        let mut min: Option<A> = None;
+        let mut max: Option<A> = None;
         for &p in &self.players {
             min = Some(min.map(|v| if v < p { v } else { p }).unwrap_or(p));
             max = Some(max.map(|v| if v > p { v } else { p }).unwrap_or(p));
         }
-        (min.unwrap_or(0.0), max.unwrap_or(0.0))
+        (min.unwrap_or_else(A::zero), max.unwrap_or_else(A::zero))
     }
 
     /// Find the Gini coefficient (see [Wikipedia article](https://en.wikipedia.org/wiki/Gini_coefficient)).
+    ///
+    /// Sorts a copy of the fortunes, which takes `O(n log n)` time.
     pub fn gini(&self) -> f64 {
+        let mut values: Vec<f64> = self.players.iter().map(|p| p.to_f64()).collect();
+        stats::gini(&mut values)
+    }
+
+    /// Measures the fortunes of all players.
+    pub fn metric(&self, metric: Metric) -> f64 {
+        let mut values: Vec<f64> = self.players.iter().map(|p| p.to_f64()).collect();
+        metric.measure(&mut values)
+    }
+
+    /// Find the Gini coefficient by comparing all pairs of players.
+    ///
+    /// Takes `O(n^2)` time. This is kept as a reference for `Economy::gini`.
+    pub fn gini_quadratic(&self) -> f64 {
         let mut sum = 0.0;
         let n = self.players.len();
         for i in 0..n {
             for j in 0..n {
-                sum += (self.players[i] - self.players[j]).abs();
+                sum += (self.players[i].to_f64() - self.players[j].to_f64()).abs();
             }
         }
         let mut div = 0.0;
         for j in 0..n {
-            div += self.players[j] * n as f64;
+            div += self.players[j].to_f64() * n as f64;
         }
         sum / (2.0 * div)
     }
 
     /// Does a transaction between two people.
-    pub fn transaction(&mut self, from: usize, to: usize, amount: f64)
-    -> Result<(), ()> {
-        if from == to { return Err(()); }
-        let new_fortune = self.players[from] - amount;
-        if new_fortune > 0.0 {
-            self.players[to] += amount;
-            self.players[from] = new_fortune;
-            Ok(())
-        } else {
-            Err(())
+    ///
+    /// Never panics. Returns an error telling why the transaction
+    /// was rejected, in which case no fortune is changed.
+    pub fn transaction(&mut self, from: PlayerId, to: PlayerId, amount: A)
+    -> Result<(), TransactionError> {
+        let (i, j) = self.transaction_indices(from, to, amount)?;
+        self.players[i] -= amount;
+        self.players[j] += amount;
+        self.record(Event::Transaction {from, to, amount});
+        Ok(())
+    }
+
+    /// Checks whether a transaction would succeed, without doing it.
+    ///
+    /// Never panics. Returns the same error as `Economy::transaction` would.
+    pub fn check_transaction(&self, from: PlayerId, to: PlayerId, amount: A)
+    -> Result<(), TransactionError> {
+        self.transaction_indices(from, to, amount).map(|_| ())
+    }
+
+    /// Checks a transaction, returning the indices of the players into `Economy::fortunes`.
+    ///
+    /// The indices are only valid until players leave.
+    pub(crate) fn transaction_indices(&self, from: PlayerId, to: PlayerId, amount: A)
+    -> Result<(usize, usize), TransactionError> {
+        if !amount.to_f64().is_finite() || amount < A::zero() {
+            return Err(TransactionError::InvalidAmount(amount.to_f64()));
         }
+        if from == to { return Err(TransactionError::SamePlayer); }
+        let from_index = self.slots.get(from)
+            .ok_or(TransactionError::UnknownPlayer(from))?;
+        let to_index = self.slots.get(to)
+            .ok_or(TransactionError::UnknownPlayer(to))?;
+        if amount >= self.players[from_index] {
+            return Err(TransactionError::InsufficientFunds);
+        }
+        self.players[to_index].checked_add(amount).ok_or(TransactionError::Overflow)?;
+        Ok((from_index, to_index))
+    }
+
+    /// Pays money from the treasury to a player.
+    ///
+    /// Returns an error if the treasury does not have enough money,
+    /// in which case nothing is changed.
+    pub fn pay_from_treasury(&mut self, to: PlayerId, amount: A, purpose: Purpose)
+    -> Result<(), TransactionError> {
+        self.treasury.check_withdraw(amount)?;
+        let i = self.slots.get(to).ok_or(TransactionError::UnknownPlayer(to))?;
+        self.players[i].checked_add(amount).ok_or(TransactionError::Overflow)?;
+        self.treasury.withdraw(amount, purpose);
+        self.players[i] += amount;
+        self.record(Event::TreasuryPayment {to, amount, purpose});
+        Ok(())
+    }
+
+    /// Spends money from the treasury outside the economy,
+    /// for example on purchases made by non-player characters.
+    ///
+    /// The money disappears from the economy.
+    pub fn spend_from_treasury(&mut self, amount: A, purpose: Purpose)
+    -> Result<(), TransactionError> {
+        self.treasury.check_withdraw(amount)?;
+        self.treasury.withdraw(amount, purpose);
+        self.record(Event::TreasurySpend {amount, purpose});
+        Ok(())
     }
 
     /// Updates the economy using the fixed tax rate.
     /// The Gini index can vary depending on economic activity.
-    pub fn update(&mut self) {
+    ///
+    /// Returns a report telling how much money was burned and distributed.
+    pub fn update(&mut self) -> UpdateReport {
+        if self.ledger.tax != self.tax {
+            self.record(Event::TaxChange {tax: self.tax});
+        }
+        let mut scratch = Scratch::new();
+        let mut burn_carry = self.burn_carry;
+        let mut reward_carry = self.reward_carry;
+        let report = self.update_into(self.tax, &mut burn_carry, &mut reward_carry, &mut scratch);
+        self.audits.clear();
+        if self.audit_updates {
+            let limit = self.soft_limit.to_f64();
+            let start_fortune = self.start_fortune.to_f64() / limit;
+            for i in 0..self.players.len() {
+                self.audits.push(Audit {
+                    player: self.slots.id_at(i),
+                    tax: report.tax,
+                    before: self.players[i],
+                    after: scratch.fortunes[i],
+                    burned: self.players[i].to_f64() >= limit,
+                    burn: scratch.burns[i],
+                    rewarded: false,
+                    reward: scratch.rewards[i],
+                    weight: scratch.weights[i],
+                    weighted_fortune: (self.players[i].to_f64() - scratch.burns[i].to_f64()) / limit,
+                    floored: false,
+                });
+            }
+            // The players rewarded are left in the scratch indices.
+            for &i in &scratch.indices {
+                let audit = &mut self.audits[i];
+                audit.rewarded = true;
+                audit.floored = audit.weighted_fortune < start_fortune;
+            }
+        }
+        self.index_audits();
+        std::mem::swap(&mut self.players, &mut scratch.fortunes);
+        let mut collected = A::zero();
+        if self.collect_burns && self.budget == Budget::ChargeByLack {
+            collected = scratch.burns.iter().fold(A::zero(), |sum, &x| sum + x);
+            self.treasury.collect_burn(collected);
+        }
+        self.burn_carry = burn_carry;
+        self.reward_carry = reward_carry;
+        if self.record_ledger {
+            for (i, &amount) in scratch.burns.iter().enumerate() {
+                if amount != A::zero() {
+                    self.record(Event::Burn {player: self.slots.id_at(i), amount});
+                }
+            }
+            for (i, &amount) in scratch.rewards.iter().enumerate() {
+                if amount != A::zero() {
+                    self.record(Event::Reward {player: self.slots.id_at(i), amount});
+                }
+            }
+            self.record(Event::Update {collected, burn_carry, reward_carry});
+        }
+        report
+    }
+
+    /// Projects how the next update would change the fortune of a player,
+    /// using the current tax, without changing the economy.
+    ///
+    /// This lets players see how much money burns in their pockets
+    /// unless they spend it.
+    /// Returns `None` if the player is not in the economy.
+    pub fn projection(&self, id: PlayerId) -> Option<Projection<A>> {
+        let i = self.slots.get(id)?;
+        let mut scratch = Scratch::new();
+        self.preview_update_into(self.tax, &mut scratch);
+        Some(scratch.projection(&self.players, i))
+    }
+
+    /// Projects how the next update would change the fortunes of all players,
+    /// using the current tax, without changing the economy.
+    ///
+    /// The order is the same as `Economy::fortunes`.
+    pub fn projections(&self, scratch: &mut Scratch<A>) -> Vec<Projection<A>> {
+        self.preview_update_into(self.tax, scratch);
+        (0..self.players.len()).map(|i| scratch.projection(&self.players, i)).collect()
+    }
+
+    /// Returns the ledger of changes to the economy.
+    pub fn ledger(&self) -> &Ledger<A> {
+        &self.ledger
+    }
+
+    /// Moves the entries recorded in the ledger out, for example to store them.
+    ///
+    /// Sequence numbers of later entries continue where these ended.
+    pub fn take_ledger_entries(&mut self) -> Vec<Entry<A>> {
+        self.ledger.take()
+    }
+
+    fn record(&mut self, event: Event<A>) {
+        if self.record_ledger {
+            self.ledger.push(event);
+        }
+    }
+
+    /// Returns how the last update changed a player,
+    /// if `Economy::audit_updates` was enabled.
+    ///
+    /// Returns `None` if the player was not in the economy at the last update.
+    /// Takes constant time.
+    pub fn audit(&self, id: PlayerId) -> Option<&Audit<A>> {
+        let audit = &self.audits[(*self.audit_positions.get(id.slot())?)?];
+        if audit.player == id { Some(audit) } else { None }
+    }
+
+    // Finds the position of the audit of each player, to look up audits by id.
+    pub(crate) fn index_audits(&mut self) {
+        self.audit_positions.clear();
+        for (i, audit) in self.audits.iter().enumerate() {
+            let slot = audit.player.slot();
+            if slot >= self.audit_positions.len() {
+                self.audit_positions.resize(slot + 1, None);
+            }
+            self.audit_positions[slot] = Some(i);
+        }
+    }
+
+    /// Returns how the last update changed each player,
+    /// if `Economy::audit_updates` was enabled.
+    pub fn audits(&self) -> &[Audit<A>] {
+        &self.audits
+    }
+
+    /// Returns the fortunes after an update using some tax,
+    /// without changing the economy.
+    ///
+    /// The order is the same as `Economy::fortunes`.
+    pub fn preview_update(&self, tax: f64) -> Vec<A> {
+        let mut scratch = Scratch::new();
+        let (mut burn_carry, mut reward_carry) = (self.burn_carry, self.reward_carry);
+        self.update_into(tax, &mut burn_carry, &mut reward_carry, &mut scratch);
+        scratch.fortunes
+    }
+
+    /// Returns the Gini coefficient after an update using some tax,
+    /// without changing the economy.
+    pub fn preview_gini(&self, tax: f64, scratch: &mut Scratch<A>) -> f64 {
+        self.preview_metric(tax, Metric::Gini, scratch)
+    }
+
+    /// Measures the fortunes after an update using some tax,
+    /// without changing the economy.
+    pub fn preview_metric(&self, tax: f64, metric: Metric, scratch: &mut Scratch<A>) -> f64 {
+        self.preview_update_into(tax, scratch);
+        scratch.values.clear();
+        scratch.values.extend(scratch.fortunes.iter().map(|p| p.to_f64()));
+        metric.measure(&mut scratch.values)
+    }
+
+    /// Computes an update using some tax, storing the result in scratch,
+    /// without changing the economy.
+    pub(crate) fn preview_update_into(&self, tax: f64, scratch: &mut Scratch<A>)
+    -> UpdateReport {
+        let (mut burn_carry, mut reward_carry) = (self.burn_carry, self.reward_carry);
+        self.update_into(tax, &mut burn_carry, &mut reward_carry, scratch)
+    }
+
+    /// Computes an update using some tax, storing the result in scratch.
+    fn update_into(
+        &self,
+        tax: f64,
+        burn_carry: &mut f64,
+        reward_carry: &mut f64,
+        scratch: &mut Scratch<A>,
+    ) -> UpdateReport {
+        let limit = self.soft_limit.to_f64();
+        let start_fortune = self.start_fortune.to_f64() / limit;
+        let n = self.players.len();
+        scratch.fortunes.clear();
+        scratch.fortunes.extend_from_slice(&self.players);
+        scratch.burns.clear();
+        scratch.burns.resize(n, A::zero());
+        scratch.rewards.clear();
+        scratch.rewards.resize(n, A::zero());
+        scratch.weights.clear();
+        scratch.weights.resize(n, 0.0);
+
         // Remove wealth from rich players.
-        for p in &mut self.players {
-            if *p >= 1.0 {
-                let amount = (*p - 1.0).sqrt() * self.tax;
-                *p -= amount;
+        scratch.indices.clear();
+        scratch.exact.clear();
+        for (i, p) in scratch.fortunes.iter().enumerate() {
+            let p = p.to_f64();
+            if p >= limit {
+                scratch.indices.push(i);
+                // No player can lose more than the whole fortune.
+                scratch.exact.push((limit * self.burn_curve.burn(p / limit - 1.0) * tax).min(p));
             }
         }
+        let burns: Vec<A> = round_amounts(&scratch.exact, burn_carry, self.rounding);
+        let mut burned = 0.0;
+        for (&i, &amount) in scratch.indices.iter().zip(&burns) {
+            // Rounding up can exceed the fortune by a unit, which is carried instead.
+            let fortune = scratch.fortunes[i];
+            let amount = if amount > fortune {
+                *burn_carry += amount.to_f64() - fortune.to_f64();
+                fortune
+            } else {
+                amount
+            };
+            scratch.burns[i] = amount;
+            scratch.fortunes[i] -= amount;
+            burned += amount.to_f64();
+        }
+        let above_limit = scratch.indices.len();
 
         // Compute weights and how much to distribute.
         let mut sum_weights = 0.0;
         let mut distribute = 0.0;
-        for p in &self.players {
-            if *p < 1.0 {
-                distribute += 1.0 - *p;
-                if *p < self.start_fortune {
-                    sum_weights += self.start_fortune.sqrt();
-                } else {
-                    sum_weights += p.sqrt();
-                }
+        scratch.indices.clear();
+        scratch.exact.clear();
+        for (i, p) in scratch.fortunes.iter().enumerate() {
+            let p = p.to_f64();
+            if p < limit {
+                let weight = self.reward_weighting.weight(p / limit, start_fortune);
+                distribute += limit - p;
+                sum_weights += weight;
+                scratch.indices.push(i);
+                scratch.weights[i] = weight;
             }
         }
 
+        let below_limit = scratch.indices.len();
+
         // Distribute the wealth among poor players.
-        for p in &mut self.players {
-            if *p < 1.0 {
-                if *p < self.start_fortune {
-                    *p += self.start_fortune.sqrt() / sum_weights *
-                        distribute * self.tax;
-                } else {
-                    *p += p.sqrt() / sum_weights * distribute * self.tax;
+        let rewards: Vec<A> = match self.budget {
+            Budget::ChargeByLack => {
+                for &i in &scratch.indices {
+                    scratch.exact.push(scratch.weights[i] / sum_weights * distribute * tax);
+                }
+                round_amounts(&scratch.exact, reward_carry, self.rounding)
+            }
+            Budget::Balanced => {
+                if scratch.indices.is_empty() && burned != 0.0 {
+                    for (i, p) in scratch.fortunes.iter().enumerate() {
+                        let weight = self.reward_weighting.weight(p.to_f64() / limit, start_fortune);
+                        sum_weights += weight;
+                        scratch.indices.push(i);
+                        scratch.weights[i] = weight;
+                    }
+                }
+                for &i in &scratch.indices {
+                    scratch.exact.push(scratch.weights[i] / sum_weights * burned);
                 }
+                // Rounding to nearest without carry keeps the sum equal to what was burned.
+                round_amounts(&scratch.exact, &mut 0.0, Rounding::Nearest)
             }
+        };
+        let mut distributed = 0.0;
+        for (&i, &amount) in scratch.indices.iter().zip(&rewards) {
+            scratch.rewards[i] = amount;
+            scratch.fortunes[i] += amount;
+            distributed += amount.to_f64();
         }
-    }
 
-    /// Updates the economy using a target Gini coefficient.
-    /// The tax is automatically adjusted to meet the target.
-    /// Uses convergent binary search to find the tax.
-    ///
-    /// The solver is less accurate for high Gini (`~0.5` or higher) in some cases.
-    /// A very low Gini (`<0.1`) might not work at all, because the algorithm
-    /// is incentivizing (players that have more gets more below the upper soft limit).
-    ///
-    /// The `smooth_target` parameter is a value in range `[0.5, 1)`.
-    /// `0.5` gives binary search behavior, which assumes strict
-    /// monotonic Gini (tax should be lowered if target Gini is above).
-    /// Higher values weakens the assumption, interpreted as
-    /// the mix-algorithm "tends to have" monotonic Gini.
-    ///
-    /// The `min_tax` parameter is a value usually above 0,
-    /// to prevent the solver from getting stuck in 0% scenarios.
-    pub fn solve(
-        &mut self,
-        target_gini: f64,
-        smooth_target: f64,
-        min_tax: f64,
-    ) {
-        let mut tax = 0.0;
-        let mut step = 0.5;
-        loop {
-            if tax > 1.0 { break; }
-            let mut copy = self.clone();
-            copy.tax = tax;
-            copy.update();
-            let gini = copy.gini();
-            let diff = target_gini - gini;
-            if diff > 0.0 {
-                tax -= step;
+        UpdateReport {
+            tax,
+            burned,
+            distributed,
+            net: distributed - burned,
+            collected: if self.collect_burns && self.budget == Budget::ChargeByLack {
+                burned
             } else {
-                tax += step;
-            }
-            step *= smooth_target;
-            if step < 0.0001 { break; }
+                0.0
+            },
+            above_limit,
+            below_limit,
         }
-
-        if tax < min_tax { tax = min_tax; }
-        if tax > 1.0 { tax = 1.0; }
-        self.tax = tax;
-        self.update();
     }
 }
@@ -0,0 +1,68 @@
+//! Metrics of the distribution of fortunes.
+
+use stats;
+
+/// A measure of fortunes that the solver can target.
+///
+/// Without players, every metric is NaN like `Economy::gini`,
+/// except `TotalSupply`, which is zero.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Metric {
+    /// The Gini coefficient.
+    Gini,
+    /// The sum of all fortunes, in currency.
+    TotalSupply,
+    /// The mean fortune, in currency.
+    Mean,
+    /// The median fortune, in currency.
+    ///
+    /// To target a median relative to start fortune,
+    /// multiply the start fortune with the relative target.
+    Median,
+    /// The share of all money held by the richest fraction of players.
+    ///
+    /// For example, `TopShare(0.1)` is the share held by the richest 10%.
+    TopShare(f64),
+}
+
+impl Metric {
+    /// Measures fortunes converted to `f64`.
+    ///
+    /// The values might be reordered.
+    pub fn measure(&self, values: &mut [f64]) -> f64 {
+        match *self {
+            Metric::Gini => stats::gini(values),
+            Metric::TotalSupply => values.iter().sum(),
+            Metric::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            Metric::Median => {
+                let n = values.len();
+                if n == 0 { return f64::NAN; }
+                values.sort_by(|a, b| a.total_cmp(b));
+                if n % 2 == 1 {
+                    values[n / 2]
+                } else {
+                    0.5 * (values[n / 2 - 1] + values[n / 2])
+                }
+            }
+            Metric::TopShare(fraction) => {
+                values.sort_by(|a, b| a.total_cmp(b));
+                let n = values.len();
+                if n == 0 { return f64::NAN; }
+                let top = ((fraction * n as f64).ceil() as usize).min(n);
+                let total: f64 = values.iter().sum();
+                values[n - top..].iter().sum::<f64>() / total
+            }
+        }
+    }
+
+    /// Returns `true` if higher tax is known to lower the value.
+    ///
+    /// Money supply and median can move either way,
+    /// depending on how much is burned compared to what is distributed.
+    pub fn decreasing_in_tax(&self) -> bool {
+        match *self {
+            Metric::Gini | Metric::TopShare(_) => true,
+            Metric::TotalSupply | Metric::Mean | Metric::Median => false,
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Stable player handles.
+
+/// Refers to a player in an economy.
+///
+/// A player id stays valid when other players leave the economy.
+/// When a player leaves, the id is never handed out again,
+/// because the generation of the slot is bumped.
+/// A slot is retired instead of reused when its generation runs out.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PlayerId {
+    index: u32,
+    generation: u32,
+}
+
+impl PlayerId {
+    /// Returns the index of the slot, which is kept by the player while in the economy.
+    pub(crate) fn slot(&self) -> usize {
+        self.index as usize
+    }
+}
+
+/// Tells what happens to the fortune of a player leaving the economy.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Departure {
+    /// The fortune disappears from the economy.
+    Burn,
+    /// The fortune is shared equally among the remaining players.
+    ///
+    /// When no players remain, the fortune disappears.
+    Redistribute,
+    /// The fortune is moved to the treasury.
+    Treasury,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct Slot {
+    generation: u32,
+    // Index into the dense player storage, `None` if the slot is free.
+    dense: Option<usize>,
+}
+
+/// Maps player ids to indices in dense player storage.
+///
+/// Removing a player swaps the last player into its place,
+/// such that fortunes can be stored in a vector without holes.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct Slots {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    // The slot index of each player in dense storage.
+    owners: Vec<u32>,
+}
+
+impl Slots {
+    /// Creates a new player at the end of dense storage.
+    pub fn insert(&mut self) -> PlayerId {
+        let dense = self.owners.len();
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.slots[index as usize].dense = Some(dense);
+                index
+            }
+            None => {
+                self.slots.push(Slot {generation: 0, dense: Some(dense)});
+                (self.slots.len() - 1) as u32
+            }
+        };
+        self.owners.push(index);
+        PlayerId {index, generation: self.slots[index as usize].generation}
+    }
+
+    /// Returns the dense index of a player.
+    pub fn get(&self, id: PlayerId) -> Option<usize> {
+        match self.slots.get(id.index as usize) {
+            Some(slot) if slot.generation == id.generation => slot.dense,
+            _ => None,
+        }
+    }
+
+    /// Removes a player, returning the dense index it occupied.
+    ///
+    /// The caller must `swap_remove` the same index from dense storage.
+    pub fn remove(&mut self, id: PlayerId) -> Option<usize> {
+        let dense = self.get(id)?;
+        let slot = &mut self.slots[id.index as usize];
+        slot.dense = None;
+        if let Some(generation) = slot.generation.checked_add(1) {
+            slot.generation = generation;
+            self.free.push(id.index);
+        }
+        self.owners.swap_remove(dense);
+        if let Some(&moved) = self.owners.get(dense) {
+            self.slots[moved as usize].dense = Some(dense);
+        }
+        Some(dense)
+    }
+
+    /// Returns the number of slots, including free and retired slots.
+    #[cfg(feature = "serde")]
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Checks that the slots agree with dense storage of some length,
+    /// for example after restoring a saved economy.
+    #[cfg(feature = "serde")]
+    pub fn check(&self, players: usize) -> Result<(), String> {
+        if self.owners.len() != players {
+            return Err(format!("Expected `{}` slot owners, found `{}`",
+                players, self.owners.len()));
+        }
+        for (dense, &index) in self.owners.iter().enumerate() {
+            match self.slots.get(index as usize) {
+                Some(slot) if slot.dense == Some(dense) => {}
+                _ => return Err(format!("Slot `{}` does not point back at player `{}`",
+                    index, dense)),
+            }
+        }
+        let used = self.slots.iter().filter(|slot| slot.dense.is_some()).count();
+        if used != players {
+            return Err(format!("Expected `{}` used slots, found `{}`", players, used));
+        }
+        let mut free = vec![false; self.slots.len()];
+        for &index in &self.free {
+            match self.slots.get(index as usize) {
+                Some(slot) if slot.dense.is_none() && !free[index as usize] => {
+                    free[index as usize] = true;
+                }
+                _ => return Err(format!("Invalid free slot `{}`", index)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the id of the player at a dense index.
+    pub fn id_at(&self, dense: usize) -> PlayerId {
+        let index = self.owners[dense];
+        PlayerId {index, generation: self.slots[index as usize].generation}
+    }
+}
@@ -0,0 +1,25 @@
+//! Projections of the next update for single players.
+
+use Amount;
+
+/// Tells how the next update would change the fortune of a player.
+///
+/// Amounts are in currency.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Projection<A = f64> {
+    /// The fortune before the update.
+    pub fortune: A,
+    /// The money that would be burned, because the fortune is above the soft limit.
+    pub burn: A,
+    /// The money that would be rewarded, because the fortune is below the soft limit.
+    pub reward: A,
+    /// The fortune after the update.
+    pub next_fortune: A,
+}
+
+impl<A: Amount> Projection<A> {
+    /// Returns the change of fortune, which is reward minus burn.
+    pub fn net(&self) -> f64 {
+        self.reward.to_f64() - self.burn.to_f64()
+    }
+}
@@ -0,0 +1,27 @@
+//! Reports from updating the economy.
+
+/// Describes how money moved in an update.
+///
+/// Amounts are in currency.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct UpdateReport {
+    /// The tax used.
+    pub tax: f64,
+    /// The total money burned from players above the soft limit.
+    pub burned: f64,
+    /// The total money distributed to players below the soft limit.
+    pub distributed: f64,
+    /// The change of money supply held by players, which is distributed minus burned.
+    pub net: f64,
+    /// The money collected by the treasury.
+    pub collected: f64,
+    /// The number of players at or above the soft limit before update,
+    /// who were burned.
+    pub above_limit: usize,
+    /// The number of players below the soft limit after burning,
+    /// who were rewarded.
+    ///
+    /// With `Budget::Balanced`, when this is zero,
+    /// every player is rewarded with a share of what was burned.
+    pub below_limit: usize,
+}
@@ -0,0 +1,106 @@
+//! Reward weightings for fortunes below the soft limit.
+
+use std::sync::Arc;
+
+/// Computes the share of rewards given to a player below the soft limit.
+///
+/// Each player receives its weight divided by the sum of weights,
+/// multiplied with the money to distribute.
+/// A weighting that grows with fortune rewards saving money.
+pub trait RewardWeighting {
+    /// Returns the weight of a normalized `fortune` below the soft limit.
+    fn weight(&self, fortune: f64, start_fortune: f64) -> f64;
+
+    /// Returns the configuration of a built-in weighting, used for saving the economy.
+    ///
+    /// Returns `None` by default, for custom weightings.
+    fn config(&self) -> Option<RewardConfig> { None }
+}
+
+/// Describes one of the built-in reward weightings.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RewardConfig {
+    /// Uses `FlatReward`.
+    Flat,
+    /// Uses `SqrtReward`.
+    Sqrt,
+    /// Uses `LinearReward`.
+    Linear,
+    /// Uses `CappedReward` with a cap.
+    Capped(f64),
+}
+
+impl RewardConfig {
+    /// Creates the reward weighting.
+    pub fn weighting(&self) -> Arc<dyn RewardWeighting + Send + Sync> {
+        match *self {
+            RewardConfig::Flat => Arc::new(FlatReward),
+            RewardConfig::Sqrt => Arc::new(SqrtReward),
+            RewardConfig::Linear => Arc::new(LinearReward),
+            RewardConfig::Capped(cap) => Arc::new(CappedReward {cap}),
+        }
+    }
+}
+
+/// Gives every player the same reward, like a universal basic income.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FlatReward;
+
+impl RewardWeighting for FlatReward {
+    fn weight(&self, _fortune: f64, _start_fortune: f64) -> f64 {
+        1.0
+    }
+
+    fn config(&self) -> Option<RewardConfig> { Some(RewardConfig::Flat) }
+}
+
+/// Weights by the square root of fortune,
+/// using the start fortune for poorer players.
+///
+/// This is the default weighting.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SqrtReward;
+
+impl RewardWeighting for SqrtReward {
+    fn weight(&self, fortune: f64, start_fortune: f64) -> f64 {
+        if fortune < start_fortune {
+            start_fortune.sqrt()
+        } else {
+            fortune.sqrt()
+        }
+    }
+
+    fn config(&self) -> Option<RewardConfig> { Some(RewardConfig::Sqrt) }
+}
+
+/// Weights by fortune, using the start fortune for poorer players.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LinearReward;
+
+impl RewardWeighting for LinearReward {
+    fn weight(&self, fortune: f64, start_fortune: f64) -> f64 {
+        fortune.max(start_fortune)
+    }
+
+    fn config(&self) -> Option<RewardConfig> { Some(RewardConfig::Linear) }
+}
+
+/// Weights by the square root of fortune,
+/// using the start fortune for poorer players and `cap` for richer players.
+///
+/// Limits how much more players with more money get.
+#[derive(Copy, Clone, Debug)]
+pub struct CappedReward {
+    /// The normalized fortune above which the weight stops growing.
+    pub cap: f64,
+}
+
+impl RewardWeighting for CappedReward {
+    fn weight(&self, fortune: f64, start_fortune: f64) -> f64 {
+        SqrtReward.weight(fortune.min(self.cap), start_fortune)
+    }
+
+    fn config(&self) -> Option<RewardConfig> { Some(RewardConfig::Capped(self.cap)) }
+}
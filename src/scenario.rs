@@ -0,0 +1,419 @@
+//! Scenarios describing a whole simulation, for running studies without code.
+
+use std::io::{self, Write};
+
+use {
+    Budget,
+    BurnConfig,
+    ConvergingAverage,
+    Economy,
+    Metric,
+    RewardConfig,
+    Simulation,
+    SimulationSettings,
+    SolverStrategy,
+    TaxController,
+    UpdateReport,
+    WindowedMean,
+};
+
+/// Describes a simulation of random transactions in an economy.
+///
+/// With the `serde` feature, a scenario can be read from a file,
+/// where every section and field is optional.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Scenario {
+    /// The economy parameters.
+    pub economy: ScenarioEconomy,
+    /// The transaction model.
+    pub transactions: ScenarioTransactions,
+    /// How the tax is regulated.
+    pub regulation: Regulation,
+    /// How long to run.
+    pub duration: ScenarioDuration,
+}
+
+/// The economy parameters of a scenario.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct ScenarioEconomy {
+    /// The tax, or the initial tax when regulated.
+    ///
+    /// Is `0` by default.
+    pub tax: f64,
+    /// The start fortune of players, in currency.
+    ///
+    /// Is `0.25` by default.
+    pub start_fortune: f64,
+    /// The soft limit of fortunes, in currency.
+    ///
+    /// Is `1` by default.
+    pub soft_limit: f64,
+    /// The number of players.
+    ///
+    /// Is `100` by default.
+    pub players: usize,
+    /// The burn curve. Uses `BurnConfig::Sqrt` by default.
+    pub burn_curve: BurnConfig,
+    /// The reward weighting. Uses `RewardConfig::Sqrt` by default.
+    pub reward_weighting: RewardConfig,
+    /// Where rewards come from. Uses `Budget::ChargeByLack` by default.
+    pub budget: Budget,
+    /// Whether burned money goes to the treasury. Is `false` by default.
+    pub collect_burns: bool,
+}
+
+impl Default for ScenarioEconomy {
+    fn default() -> ScenarioEconomy {
+        ScenarioEconomy {
+            tax: 0.0,
+            start_fortune: 0.25,
+            soft_limit: 1.0,
+            players: 100,
+            burn_curve: BurnConfig::Sqrt,
+            reward_weighting: RewardConfig::Sqrt,
+            budget: Budget::ChargeByLack,
+            collect_burns: false,
+        }
+    }
+}
+
+/// The transaction model of a scenario.
+///
+/// Players make transactions of a fixed amount to random players.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct ScenarioTransactions {
+    /// The seed of the random generator. Is `0` by default.
+    pub seed: u64,
+    /// The fixed amount of each transaction, in currency.
+    ///
+    /// Is `0.03` by default.
+    pub avg_trans: f64,
+    /// The number of transactions between each update.
+    ///
+    /// Is `1000` by default.
+    pub trans: u32,
+}
+
+impl Default for ScenarioTransactions {
+    fn default() -> ScenarioTransactions {
+        ScenarioTransactions {
+            seed: 0,
+            avg_trans: 0.03,
+            trans: 1000,
+        }
+    }
+}
+
+/// Tells how the tax is regulated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RegulationMode {
+    /// Uses the fixed tax.
+    Fixed,
+    /// Uses `Economy::solve` with a search.
+    Solve,
+    /// Uses `Economy::solve_with` with bisection.
+    Bisection,
+    /// Uses a `TaxController`.
+    Controller,
+}
+
+/// How the tax is regulated in a scenario.
+///
+/// Only the fields used by the mode matter.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Regulation {
+    /// The regulation mode. Is `RegulationMode::Fixed` by default.
+    pub mode: RegulationMode,
+    /// The target Gini coefficient. Is `0.3` by default.
+    pub target_gini: f64,
+    /// How much to smooth the solved tax. Is `0.9` by default.
+    pub smooth_target: f64,
+    /// The minimum tax. Is `0` by default.
+    pub min_tax: f64,
+    /// The maximum tax of the controller. Is `1` by default.
+    pub max_tax: f64,
+    /// The tolerance of bisection. Is `1e-6` by default.
+    pub tolerance: f64,
+    /// The maximum iterations of bisection. Is `64` by default.
+    pub max_iterations: u32,
+    /// The proportional gain of the controller.
+    pub kp: f64,
+    /// The integral gain of the controller.
+    pub ki: f64,
+    /// The derivative gain of the controller.
+    pub kd: f64,
+    /// The maximum change of tax per update of the controller.
+    pub max_step: f64,
+}
+
+impl Default for Regulation {
+    fn default() -> Regulation {
+        let controller = TaxController::new(0.3);
+        Regulation {
+            mode: RegulationMode::Fixed,
+            target_gini: controller.target_gini,
+            smooth_target: 0.9,
+            min_tax: controller.min_tax,
+            max_tax: controller.max_tax,
+            tolerance: 1e-6,
+            max_iterations: 64,
+            kp: controller.kp,
+            ki: controller.ki,
+            kd: controller.kd,
+            max_step: controller.max_step,
+        }
+    }
+}
+
+/// Tells when a scenario stops.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DurationMode {
+    /// Stops after a number of updates.
+    Updates,
+    /// Stops when the smoothed Gini coefficient converges,
+    /// or after `ScenarioDuration::updates` at the latest.
+    Converge,
+}
+
+/// How long a scenario runs and how often it is measured.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct ScenarioDuration {
+    /// When to stop. Is `DurationMode::Updates` by default.
+    pub mode: DurationMode,
+    /// The number of updates, which is the most updates when converging.
+    ///
+    /// Is `1000` by default.
+    pub updates: u32,
+    /// The number of updates between each smoothed measurement.
+    ///
+    /// Is `10` by default.
+    pub periods: u32,
+    /// How fast the weight of smoothed measurements decreases.
+    ///
+    /// Is `0.99` by default.
+    pub smooth_fact: f64,
+    /// The number of updates between each row of the time series.
+    ///
+    /// Is `1` by default.
+    pub sample_every: u32,
+}
+
+impl Default for ScenarioDuration {
+    fn default() -> ScenarioDuration {
+        ScenarioDuration {
+            mode: DurationMode::Updates,
+            updates: 1000,
+            periods: 10,
+            smooth_fact: 0.99,
+            sample_every: 1,
+        }
+    }
+}
+
+/// A row of the time series of a scenario.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScenarioRow {
+    /// The number of updates so far.
+    pub update: u32,
+    /// The tax after the update.
+    pub tax: f64,
+    /// The Gini coefficient after the update.
+    pub gini: f64,
+    /// The money supply held by players after the update.
+    pub supply: f64,
+    /// The money burned in the update.
+    pub burned: f64,
+    /// The money distributed in the update.
+    pub distributed: f64,
+    /// The number of players burned in the update.
+    pub above_limit: usize,
+    /// The number of players rewarded in the update.
+    pub below_limit: usize,
+}
+
+/// Summary statistics of a scenario run.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScenarioSummary {
+    /// The number of updates.
+    pub updates: u32,
+    /// The Gini coefficient at the end.
+    pub final_gini: f64,
+    /// The tax at the end.
+    pub final_tax: f64,
+    /// The money supply held by players at the end.
+    pub final_supply: f64,
+    /// The mean Gini coefficient of the time series.
+    pub mean_gini: f64,
+    /// The standard error of the mean Gini coefficient.
+    pub gini_standard_error: f64,
+    /// The mean tax of the time series.
+    pub mean_tax: f64,
+    /// The smoothed Gini coefficient, see `ConvergingAverage`.
+    pub smooth_gini: f64,
+    /// The smoothed tax.
+    pub smooth_tax: f64,
+    /// Whether the smoothed Gini coefficient converged.
+    pub converged: bool,
+    /// The total money burned.
+    pub total_burned: f64,
+    /// The total money distributed.
+    pub total_distributed: f64,
+    /// The money in the treasury at the end.
+    pub treasury: f64,
+}
+
+/// The time series and summary of a scenario run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScenarioOutput {
+    /// The time series.
+    pub series: Vec<ScenarioRow>,
+    /// The summary statistics.
+    pub summary: ScenarioSummary,
+}
+
+impl Scenario {
+    /// Creates the simulation described by the scenario.
+    pub fn simulation(&self) -> Simulation {
+        let economy = &self.economy;
+        let settings = SimulationSettings {
+            players: economy.players,
+            avg_trans: self.transactions.avg_trans,
+            trans: self.transactions.trans,
+            periods: self.duration.periods,
+            smooth_fact: self.duration.smooth_fact,
+            // Runs at most the number of updates, like `Scenario::run`.
+            max_measurements: (self.duration.updates / self.duration.periods.max(1)).max(1) as u64,
+        };
+        let mut simulation = Simulation::new(
+            economy.tax, economy.start_fortune, settings, self.transactions.seed);
+        let e = &mut simulation.economy;
+        e.soft_limit = economy.soft_limit;
+        e.burn_curve = economy.burn_curve.curve();
+        e.reward_weighting = economy.reward_weighting.weighting();
+        e.budget = economy.budget;
+        e.collect_burns = economy.collect_burns;
+        simulation
+    }
+
+    /// Runs the scenario headless.
+    pub fn run(&self) -> ScenarioOutput {
+        let regulation = self.regulation;
+        let duration = self.duration;
+        let mut controller = TaxController::new(regulation.target_gini);
+        controller.kp = regulation.kp;
+        controller.ki = regulation.ki;
+        controller.kd = regulation.kd;
+        controller.max_step = regulation.max_step;
+        controller.min_tax = regulation.min_tax;
+        controller.max_tax = regulation.max_tax;
+        let strategy = SolverStrategy::Bisection {
+            tolerance: regulation.tolerance,
+            max_iterations: regulation.max_iterations,
+        };
+        let mut regulate = |economy: &mut Economy| -> UpdateReport {
+            match regulation.mode {
+                RegulationMode::Fixed => economy.update(),
+                RegulationMode::Solve => economy.solve(
+                    regulation.target_gini, regulation.smooth_target, regulation.min_tax
+                ).update,
+                RegulationMode::Bisection => economy.solve_with(
+                    regulation.target_gini, strategy, regulation.min_tax
+                ).update,
+                RegulationMode::Controller => {
+                    let report = economy.update();
+                    economy.tax = controller.next_tax(economy.gini(), economy.tax);
+                    report
+                }
+            }
+        };
+
+        let mut simulation = self.simulation();
+        let mut series = vec![];
+        let mut gini = ConvergingAverage::new(duration.smooth_fact);
+        let mut tax = ConvergingAverage::new(duration.smooth_fact);
+        let mut total_burned = 0.0;
+        let mut total_distributed = 0.0;
+        let mut update = 0;
+        loop {
+            let report = simulation.step_with(&mut regulate);
+            update += 1;
+            total_burned += report.burned;
+            total_distributed += report.distributed;
+            let economy = &simulation.economy;
+            if update % duration.sample_every.max(1) == 0 {
+                series.push(ScenarioRow {
+                    update,
+                    tax: economy.tax,
+                    gini: economy.gini(),
+                    supply: economy.metric(Metric::TotalSupply),
+                    burned: report.burned,
+                    distributed: report.distributed,
+                    above_limit: report.above_limit,
+                    below_limit: report.below_limit,
+                });
+            }
+            if update % duration.periods.max(1) == 0 {
+                gini.add(economy.gini());
+                tax.add(economy.tax);
+            }
+            let done = match duration.mode {
+                DurationMode::Updates => update >= duration.updates,
+                DurationMode::Converge => gini.converged() || update >= duration.updates,
+            };
+            if done { break; }
+        }
+
+        let mut gini_mean = WindowedMean::new(series.len());
+        let mut tax_mean = WindowedMean::new(series.len());
+        for row in &series {
+            gini_mean.add(row.gini);
+            tax_mean.add(row.tax);
+        }
+        let economy = &simulation.economy;
+        let summary = ScenarioSummary {
+            updates: update,
+            final_gini: economy.gini(),
+            final_tax: economy.tax,
+            final_supply: economy.metric(Metric::TotalSupply),
+            mean_gini: gini_mean.mean().unwrap_or(0.0),
+            gini_standard_error: gini_mean.standard_error().unwrap_or(0.0),
+            mean_tax: tax_mean.mean().unwrap_or(0.0),
+            smooth_gini: gini.value(),
+            smooth_tax: tax.value(),
+            converged: gini.converged(),
+            total_burned,
+            total_distributed,
+            treasury: economy.treasury.balance,
+        };
+        ScenarioOutput {series, summary}
+    }
+}
+
+impl ScenarioOutput {
+    /// Writes the time series as comma separated values with a header row.
+    pub fn write_series<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "update,tax,gini,supply,burned,distributed,above_limit,below_limit")?;
+        for row in &self.series {
+            writeln!(w, "{},{},{},{},{},{},{},{}",
+                row.update, row.tax, row.gini, row.supply,
+                row.burned, row.distributed, row.above_limit, row.below_limit)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+//! Reusable buffers for computing updates.
+
+use Projection;
+
+/// Stores buffers for computing an update without changing the economy.
+///
+/// Reusing the same scratch for many previews avoids allocating memory
+/// for every candidate tax.
+#[derive(Clone, Debug)]
+pub struct Scratch<A = f64> {
+    // The fortunes after update.
+    pub(crate) fortunes: Vec<A>,
+    // The burn and reward of each player.
+    pub(crate) burns: Vec<A>,
+    pub(crate) rewards: Vec<A>,
+    // The reward weight of each player, zero for players not rewarded.
+    pub(crate) weights: Vec<f64>,
+    // Players that are burned or rewarded, with exact amounts before rounding.
+    pub(crate) indices: Vec<usize>,
+    pub(crate) exact: Vec<f64>,
+    // Fortunes converted to `f64` for computing statistics.
+    pub(crate) values: Vec<f64>,
+}
+
+impl<A> Scratch<A> {
+    /// Creates new empty buffers.
+    pub fn new() -> Scratch<A> {
+        Scratch {
+            fortunes: vec![],
+            burns: vec![],
+            rewards: vec![],
+            weights: vec![],
+            indices: vec![],
+            exact: vec![],
+            values: vec![],
+        }
+    }
+}
+
+impl<A: Copy> Scratch<A> {
+    /// Reads the projection of a player from a computed update.
+    pub(crate) fn projection(&self, fortunes: &[A], i: usize) -> Projection<A> {
+        Projection {
+            fortune: fortunes[i],
+            burn: self.burns[i],
+            reward: self.rewards[i],
+            next_fortune: self.fortunes[i],
+        }
+    }
+}
+
+impl<A> Default for Scratch<A> {
+    fn default() -> Scratch<A> {
+        Scratch::new()
+    }
+}
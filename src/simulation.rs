@@ -0,0 +1,161 @@
+//! Reproducible simulations of random transactions.
+
+use {Amount, ConvergingAverage, Economy, UpdateReport};
+
+/// Settings for simulating random transactions.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SimulationSettings {
+    /// The number of players.
+    pub players: usize,
+    /// The fixed amount of each transaction, in currency.
+    pub avg_trans: f64,
+    /// The number of transactions between each update.
+    pub trans: u32,
+    /// The number of updates between each measurement.
+    pub periods: u32,
+    /// How fast the weight of new measurements decreases.
+    ///
+    /// Should be in the range `[0, 1)`.
+    /// Values closer to 1 run longer and give more accurate results.
+    pub smooth_fact: f64,
+    /// The most measurements of a run, which stops even when not converged.
+    pub max_measurements: u64,
+}
+
+/// The smoothed measurements of a simulation run.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SimulationSummary {
+    /// The smoothed Gini coefficient.
+    pub gini: f64,
+    /// The smoothed tax.
+    pub tax: f64,
+    /// The weight a new measurement would get.
+    pub smooth: f64,
+    /// The number of measurements.
+    pub measurements: u64,
+    /// Whether the measurements converged before reaching the most measurements.
+    pub converged: bool,
+}
+
+/// Simulates random transactions between players in an economy.
+///
+/// Runs are reproducible bit for bit given the same seed,
+/// because the random generator is part of the simulation.
+/// Two simulations with the same seed and number of players
+/// make the same transactions, such that economies can be compared side by side.
+#[derive(Clone)]
+pub struct Simulation<A = f64> {
+    /// The economy simulated.
+    pub economy: Economy<A>,
+    /// The simulation settings.
+    pub settings: SimulationSettings,
+    seed: u64,
+    rng: SplitMix64,
+}
+
+impl<A: Amount> Simulation<A> {
+    /// Creates a new simulation with a new economy.
+    pub fn new(tax: f64, start_fortune: A, settings: SimulationSettings, seed: u64)
+    -> Simulation<A> {
+        Simulation {
+            economy: Economy::new(tax, start_fortune, settings.players),
+            settings,
+            seed,
+            rng: SplitMix64(seed),
+        }
+    }
+
+    /// Returns the seed the simulation was created with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Makes random transactions of a fixed amount between players.
+    ///
+    /// Transactions rejected by the economy are skipped.
+    /// Returns the number of transactions made.
+    pub fn transactions(&mut self) -> u32 {
+        let ids: Vec<_> = self.economy.ids().collect();
+        if ids.is_empty() { return 0; }
+        let amount = A::from_f64(self.settings.avg_trans);
+        let mut count = 0;
+        for _ in 0..self.settings.trans {
+            let from = ids[self.rng.below(ids.len())];
+            let to = ids[self.rng.below(ids.len())];
+            if self.economy.transaction(from, to, amount).is_ok() {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Makes random transactions, then updates the economy using the fixed tax.
+    pub fn step(&mut self) -> UpdateReport {
+        self.step_with(|economy| economy.update())
+    }
+
+    /// Makes random transactions, then regulates the economy,
+    /// for example by calling `Economy::solve`.
+    pub fn step_with<F>(&mut self, regulate: F) -> UpdateReport
+        where F: FnOnce(&mut Economy<A>) -> UpdateReport
+    {
+        self.transactions();
+        regulate(&mut self.economy)
+    }
+
+    /// Runs until the smoothed measurements converge,
+    /// updating the economy using the fixed tax.
+    pub fn run(&mut self) -> SimulationSummary {
+        self.run_with(|economy| economy.update())
+    }
+
+    /// Runs until the smoothed measurements converge,
+    /// regulating the economy after each step.
+    ///
+    /// Measures the Gini coefficient and tax after each period of steps,
+    /// using a `ConvergingAverage`.
+    /// Stops after `SimulationSettings::max_measurements` when not converging,
+    /// for example when the smooth factor is out of range.
+    pub fn run_with<F>(&mut self, mut regulate: F) -> SimulationSummary
+        where F: FnMut(&mut Economy<A>) -> UpdateReport
+    {
+        let mut gini = ConvergingAverage::new(self.settings.smooth_fact);
+        let mut tax = ConvergingAverage::new(self.settings.smooth_fact);
+        while !gini.converged() && gini.measurements() < self.settings.max_measurements {
+            for _ in 0..self.settings.periods {
+                self.step_with(&mut regulate);
+            }
+            gini.add(self.economy.gini());
+            tax.add(self.economy.tax);
+        }
+        SimulationSummary {
+            gini: gini.value(),
+            tax: tax.value(),
+            smooth: gini.weight(),
+            measurements: gini.measurements(),
+            converged: gini.converged(),
+        }
+    }
+}
+
+/// A small random generator that never changes between versions,
+/// such that published results can be reproduced.
+///
+/// See [SplitMix64](https://prng.di.unimi.it/splitmix64.c).
+#[derive(Clone)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in the range `[0, n)`.
+    fn below(&mut self, n: usize) -> usize {
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+}
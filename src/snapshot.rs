@@ -0,0 +1,119 @@
+//! Saving and restoring the economy with serde.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error as DeError;
+use serde::ser::Error as SerError;
+
+use {Amount, Audit, BurnConfig, Budget, Economy, Ledger, RewardConfig, Rounding, Treasury};
+use player::Slots;
+
+/// The version of the saved economy format.
+///
+/// Bumped when the format changes. Older versions are migrated when restoring.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// The saved state of an economy.
+///
+/// Fields added in later versions must use `#[serde(default)]`,
+/// or be migrated in `Snapshot::restore`.
+#[derive(Serialize, Deserialize)]
+struct Snapshot<A> {
+    version: u32,
+    players: Vec<A>,
+    slots: Slots,
+    tax: f64,
+    burn_curve: BurnConfig,
+    reward_weighting: RewardConfig,
+    start_fortune: A,
+    soft_limit: A,
+    treasury: Treasury<A>,
+    collect_burns: bool,
+    budget: Budget,
+    rounding: Rounding,
+    audit_updates: bool,
+    audits: Vec<Audit<A>>,
+    record_ledger: bool,
+    ledger: Ledger<A>,
+    burn_carry: f64,
+    reward_carry: f64,
+}
+
+impl<A: Amount> Snapshot<A> {
+    fn save(economy: &Economy<A>) -> Result<Snapshot<A>, &'static str> {
+        Ok(Snapshot {
+            version: SNAPSHOT_VERSION,
+            players: economy.players.clone(),
+            slots: economy.slots.clone(),
+            tax: economy.tax,
+            burn_curve: economy.burn_curve.config()
+                .ok_or("A custom burn curve can not be saved")?,
+            reward_weighting: economy.reward_weighting.config()
+                .ok_or("A custom reward weighting can not be saved")?,
+            start_fortune: economy.start_fortune,
+            soft_limit: economy.soft_limit,
+            treasury: economy.treasury.clone(),
+            collect_burns: economy.collect_burns,
+            budget: economy.budget,
+            rounding: economy.rounding,
+            audit_updates: economy.audit_updates,
+            audits: economy.audits.clone(),
+            record_ledger: economy.record_ledger,
+            ledger: economy.ledger.clone(),
+            burn_carry: economy.burn_carry,
+            reward_carry: economy.reward_carry,
+        })
+    }
+
+    fn restore(self) -> Result<Economy<A>, String> {
+        if self.version > SNAPSHOT_VERSION {
+            return Err(format!("Unsupported economy version `{}`, expected at most `{}`",
+                self.version, SNAPSHOT_VERSION));
+        }
+        self.slots.check(self.players.len())?;
+        if let Some(audit) = self.audits.iter().find(|a| a.player.slot() >= self.slots.len()) {
+            return Err(format!("Audit of unknown player `{:?}`", audit.player));
+        }
+        let mut economy = Economy {
+            players: self.players,
+            slots: self.slots,
+            tax: self.tax,
+            burn_curve: self.burn_curve.curve(),
+            reward_weighting: self.reward_weighting.weighting(),
+            start_fortune: self.start_fortune,
+            soft_limit: self.soft_limit,
+            treasury: self.treasury,
+            collect_burns: self.collect_burns,
+            budget: self.budget,
+            rounding: self.rounding,
+            audit_updates: self.audit_updates,
+            audits: self.audits,
+            audit_positions: vec![],
+            record_ledger: self.record_ledger,
+            ledger: self.ledger,
+            burn_carry: self.burn_carry,
+            reward_carry: self.reward_carry,
+        };
+        economy.index_audits();
+        Ok(economy)
+    }
+}
+
+/// Saves the full state of the economy, including its version.
+///
+/// A restored economy gives identical results when the format
+/// round-trips floats exactly, such as `serde_json` with `float_roundtrip`.
+///
+/// Fails for custom burn curves and reward weightings,
+/// which have no `BurnConfig` or `RewardConfig`.
+impl<A: Amount + Serialize> Serialize for Economy<A> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Snapshot::save(self).map_err(S::Error::custom)?.serialize(serializer)
+    }
+}
+
+/// Restores an economy saved with the same or an older version.
+impl<'de, A: Amount + Deserialize<'de>> Deserialize<'de> for Economy<A> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Economy<A>, D::Error> {
+        Snapshot::deserialize(deserializer)?.restore().map_err(D::Error::custom)
+    }
+}
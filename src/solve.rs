@@ -0,0 +1,438 @@
+//! Solving tax to meet a target.
+
+use {Amount, Economy, Metric, Scratch, UpdateReport};
+
+// Used when moving tax to the boundary of an inflation bound.
+const BOUND_TOLERANCE: f64 = 1e-6;
+const BOUND_MAX_ITERATIONS: u32 = 64;
+
+/// Tells how the solver searches for tax.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SolverStrategy {
+    /// Uses convergent binary search, starting at zero tax.
+    ///
+    /// This is the algorithm used by `Economy::solve`.
+    Search {
+        /// A value in range `[0.5, 1)`.
+        ///
+        /// `0.5` gives binary search behavior, which assumes strict
+        /// monotonic Gini (tax should be lowered if target Gini is above).
+        /// For other metrics, the direction is found by comparing
+        /// the values at the minimum tax and 1.
+        /// Higher values weakens the assumption, interpreted as
+        /// the mix-algorithm "tends to have" monotonic Gini.
+        smooth_target: f64,
+    },
+    /// Uses bisection between the minimum tax and 1.
+    ///
+    /// First checks that the target lies between the values at both ends.
+    /// If not, no tax can reach the target, and the end closest to the target is used.
+    /// Otherwise, the bracket is halved until it is narrower than `tolerance`,
+    /// such that the tax is within `tolerance / 2` of a tax where the value
+    /// crosses the target.
+    Bisection {
+        /// The width of the bracket of tax to stop at.
+        tolerance: f64,
+        /// The maximum number of evaluated taxes, including both ends.
+        max_iterations: u32,
+    },
+}
+
+/// Tells which limit of the tax the solver was clamped to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Clamp {
+    /// The tax was raised to the minimum tax.
+    MinTax,
+    /// The tax was lowered to 1.
+    MaxTax,
+}
+
+/// Bounds the net money created by an update, in currency.
+///
+/// The net money created is the total reward minus the total burn.
+/// A negative value means money is removed from the economy.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct InflationBound {
+    /// The least net money to create per update.
+    pub min_net: f64,
+    /// The most net money to create per update.
+    pub max_net: f64,
+}
+
+impl InflationBound {
+    /// Returns `true` if the net money created is within bound.
+    pub fn contains(&self, net: f64) -> bool {
+        self.min_net <= net && net <= self.max_net
+    }
+
+    /// Returns how far the net money created is outside the bound.
+    pub fn violation(&self, net: f64) -> f64 {
+        if net < self.min_net {
+            self.min_net - net
+        } else if net > self.max_net {
+            net - self.max_net
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Describes how well the solver met a target Gini within an inflation bound.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoundedSolveReport {
+    /// The report of the update, targeting Gini.
+    pub solve: SolveReport,
+    /// The tax that would meet the target Gini, ignoring the bound.
+    pub gini_tax: f64,
+    /// The inflation bound.
+    pub bound: InflationBound,
+    /// Whether the tax meeting the target Gini violated the bound,
+    /// such that a compromise was used.
+    pub conflict: bool,
+}
+
+impl BoundedSolveReport {
+    /// Returns `true` if the net money created was within bound.
+    pub fn within_bound(&self) -> bool {
+        self.bound.contains(self.solve.update.net)
+    }
+}
+
+/// Describes how well the solver met its target.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SolveReport {
+    /// The tax used to update the economy.
+    pub tax: f64,
+    /// The targeted metric.
+    pub metric: Metric,
+    /// The value of the metric after the update.
+    pub value: f64,
+    /// The target value of the metric.
+    pub target: f64,
+    /// The achieved value minus the target.
+    pub error: f64,
+    /// The Gini coefficient after the update.
+    pub gini: f64,
+    /// The number of candidate taxes evaluated.
+    pub iterations: u32,
+    /// The limit the tax was clamped to, if any.
+    pub clamp: Option<Clamp>,
+    /// Whether the target lies between the values at the minimum tax and 1.
+    ///
+    /// This is `None` when the strategy does not check.
+    pub bracketed: Option<bool>,
+    /// Whether the evaluated values were monotonic in tax.
+    ///
+    /// This is `None` when the strategy does not check.
+    pub monotonic: Option<bool>,
+    /// The report of the update using the tax.
+    pub update: UpdateReport,
+}
+
+impl SolveReport {
+    /// Returns `true` if no clamp was hit and the error is within tolerance.
+    pub fn converged(&self, tolerance: f64) -> bool {
+        self.clamp.is_none() && self.error.abs() <= tolerance
+    }
+}
+
+/// The tax found by a strategy.
+pub(crate) struct Found {
+    pub tax: f64,
+    pub iterations: u32,
+    pub clamp: Option<Clamp>,
+    pub bracketed: Option<bool>,
+    pub monotonic: Option<bool>,
+}
+
+/// Finds the tax where `eval` meets the target.
+///
+/// The search strategy assumes that higher tax gives lower values
+/// when `decreasing` is `true`, and higher values otherwise.
+pub(crate) fn find_tax(
+    strategy: SolverStrategy,
+    target: f64,
+    decreasing: bool,
+    min_tax: f64,
+    eval: &mut dyn FnMut(f64) -> f64,
+) -> Found {
+    match strategy {
+        SolverStrategy::Search {smooth_target} =>
+            search(target, decreasing, smooth_target, min_tax, eval),
+        SolverStrategy::Bisection {tolerance, max_iterations} =>
+            bisection(target, tolerance, max_iterations, min_tax, 1.0, eval),
+    }
+}
+
+fn search(
+    target: f64,
+    decreasing: bool,
+    smooth_target: f64,
+    min_tax: f64,
+    eval: &mut dyn FnMut(f64) -> f64,
+) -> Found {
+    let mut tax = 0.0;
+    let mut step = 0.5;
+    let mut iterations = 0;
+    loop {
+        if tax > 1.0 { break; }
+        let value = eval(tax);
+        iterations += 1;
+        let diff = if decreasing { target - value } else { value - target };
+        if diff > 0.0 {
+            tax -= step;
+        } else {
+            tax += step;
+        }
+        step *= smooth_target;
+        if step < 0.0001 { break; }
+    }
+
+    let mut clamp = None;
+    if tax < min_tax { tax = min_tax; clamp = Some(Clamp::MinTax); }
+    if tax > 1.0 { tax = 1.0; clamp = Some(Clamp::MaxTax); }
+    Found {tax, iterations, clamp, bracketed: None, monotonic: None}
+}
+
+/// Bisects tax in range `[lo, hi]` until the value crosses the target.
+fn bisection(
+    target: f64,
+    tolerance: f64,
+    max_iterations: u32,
+    mut lo: f64,
+    mut hi: f64,
+    eval: &mut dyn FnMut(f64) -> f64,
+) -> Found {
+    let (mut value_lo, mut value_hi) = (eval(lo), eval(hi));
+    let mut iterations = 2;
+    let sign_lo = (value_lo - target).signum();
+    let sign_hi = (value_hi - target).signum();
+    if value_lo == target || value_hi == target || sign_lo == sign_hi || sign_lo.is_nan() {
+        let bracketed = value_lo == target || value_hi == target;
+        let tax_is_lo = (value_lo - target).abs() <= (value_hi - target).abs();
+        let clamp = match (bracketed, tax_is_lo) {
+            (true, _) => None,
+            (false, true) => Some(Clamp::MinTax),
+            (false, false) => Some(Clamp::MaxTax),
+        };
+        return Found {
+            tax: if tax_is_lo { lo } else { hi },
+            iterations,
+            clamp,
+            bracketed: Some(bracketed),
+            monotonic: None,
+        };
+    }
+
+    let mut monotonic = true;
+    while hi - lo > tolerance && iterations < max_iterations {
+        let mid = 0.5 * (lo + hi);
+        let value = eval(mid);
+        iterations += 1;
+        // Monotonic values stay between the values at the ends of the bracket.
+        if value < value_lo.min(value_hi) || value > value_lo.max(value_hi) {
+            monotonic = false;
+        }
+        if value == target {
+            lo = mid;
+            hi = mid;
+        } else if (value - target).signum() == sign_lo {
+            lo = mid;
+            value_lo = value;
+        } else {
+            hi = mid;
+            value_hi = value;
+        }
+    }
+    Found {
+        tax: 0.5 * (lo + hi),
+        iterations,
+        clamp: None,
+        bracketed: Some(true),
+        monotonic: Some(monotonic),
+    }
+}
+
+/// Moves tax to the closest tax where the net money created is within bound.
+///
+/// Returns the tax unchanged if it is within bound.
+/// When no boundary is found between the tax and the ends of the tax range,
+/// returns the tax that violates the bound the least.
+fn bounded_tax(
+    tax: f64,
+    bound: InflationBound,
+    min_tax: f64,
+    net: &mut dyn FnMut(f64) -> f64,
+) -> f64 {
+    let value = net(tax);
+    if bound.contains(value) { return tax; }
+    let limit = if value > bound.max_net { bound.max_net } else { bound.min_net };
+
+    // Look for the boundary toward the closest end of the tax range first.
+    let mut ends = [min_tax, 1.0];
+    if 1.0 - tax < tax - min_tax { ends.swap(0, 1); }
+    let mut best = (tax, bound.violation(value));
+    for &end in &ends {
+        let end_value = net(end);
+        if (end_value - limit).signum() != (value - limit).signum() {
+            let (lo, hi) = if end < tax { (end, tax) } else { (tax, end) };
+            let found = bisection(limit, BOUND_TOLERANCE, BOUND_MAX_ITERATIONS, lo, hi, net);
+            // Step to the side of the bracket toward the end, which is within bound,
+            // without passing the end.
+            let step = (end - found.tax).signum() * 0.5 * BOUND_TOLERANCE;
+            return (found.tax + step).max(lo).min(hi);
+        }
+        let violation = bound.violation(end_value);
+        if violation < best.1 { best = (end, violation); }
+    }
+    best.0
+}
+
+impl<A: Amount> Economy<A> {
+    /// Updates the economy using a target Gini coefficient.
+    /// The tax is automatically adjusted to meet the target.
+    /// Uses convergent binary search to find the tax.
+    /// Each candidate tax is evaluated with `Economy::preview_gini`,
+    /// reusing the same scratch buffers.
+    ///
+    /// The solver is less accurate for high Gini (`~0.5` or higher) in some cases.
+    /// A very low Gini (`<0.1`) might not work at all, because the algorithm
+    /// is incentivizing (players that have more gets more below the upper soft limit).
+    ///
+    /// The `smooth_target` parameter is a value in range `[0.5, 1)`.
+    /// `0.5` gives binary search behavior, which assumes strict
+    /// monotonic Gini (tax should be lowered if target Gini is above).
+    /// Higher values weakens the assumption, interpreted as
+    /// the mix-algorithm "tends to have" monotonic Gini.
+    ///
+    /// The `min_tax` parameter is a value usually above 0,
+    /// to prevent the solver from getting stuck in 0% scenarios.
+    ///
+    /// Returns a report telling how well the target was met.
+    pub fn solve(
+        &mut self,
+        target_gini: f64,
+        smooth_target: f64,
+        min_tax: f64,
+    ) -> SolveReport {
+        self.solve_with(target_gini, SolverStrategy::Search {smooth_target}, min_tax)
+    }
+
+    /// Updates the economy using a target Gini coefficient,
+    /// finding the tax with some strategy.
+    ///
+    /// The `min_tax` parameter is the lowest tax to use.
+    pub fn solve_with(
+        &mut self,
+        target_gini: f64,
+        strategy: SolverStrategy,
+        min_tax: f64,
+    ) -> SolveReport {
+        self.solve_metric(Metric::Gini, target_gini, strategy, min_tax)
+    }
+
+    /// Updates the economy using a target value of some metric,
+    /// finding the tax with some strategy.
+    ///
+    /// Candidate taxes are evaluated with `Economy::preview_metric`.
+    /// The `min_tax` parameter is the lowest tax to use.
+    pub fn solve_metric(
+        &mut self,
+        metric: Metric,
+        target: f64,
+        strategy: SolverStrategy,
+        min_tax: f64,
+    ) -> SolveReport {
+        let mut scratch = Scratch::new();
+        let found = {
+            let economy = &*self;
+            let mut eval = |tax| economy.preview_metric(tax, metric, &mut scratch);
+            let mut iterations = 0;
+            let decreasing = match strategy {
+                SolverStrategy::Search {..} if !metric.decreasing_in_tax() => {
+                    iterations += 2;
+                    eval(1.0) <= eval(min_tax)
+                }
+                _ => true,
+            };
+            let mut found = find_tax(strategy, target, decreasing, min_tax, &mut eval);
+            found.iterations += iterations;
+            found
+        };
+        self.tax = found.tax;
+        let update = self.update();
+        let value = self.metric(metric);
+        SolveReport {
+            tax: found.tax,
+            metric,
+            value,
+            target,
+            error: value - target,
+            gini: if metric == Metric::Gini { value } else { self.gini() },
+            iterations: found.iterations,
+            clamp: found.clamp,
+            bracketed: found.bracketed,
+            monotonic: found.monotonic,
+            update,
+        }
+    }
+
+    /// Returns the net money created by an update using some tax,
+    /// without changing the economy.
+    pub fn preview_net(&self, tax: f64, scratch: &mut Scratch<A>) -> f64 {
+        self.preview_update_into(tax, scratch).net
+    }
+
+    /// Updates the economy using a target Gini coefficient,
+    /// while keeping the net money created within a bound.
+    ///
+    /// First finds the tax meeting the target Gini with some strategy.
+    /// If that tax creates too much or too little money,
+    /// the tax is moved toward the boundary of the inflation bound,
+    /// which is the best compromise closest to the target Gini.
+    /// When no tax in `[min_tax, 1]` satisfies the bound,
+    /// the tax that violates it the least is used.
+    pub fn solve_bounded(
+        &mut self,
+        target_gini: f64,
+        bound: InflationBound,
+        strategy: SolverStrategy,
+        min_tax: f64,
+    ) -> BoundedSolveReport {
+        let mut scratch = Scratch::new();
+        let (gini_found, tax, iterations) = {
+            let economy = &*self;
+            let found = find_tax(strategy, target_gini, true, min_tax,
+                &mut |tax| economy.preview_gini(tax, &mut scratch));
+            let mut iterations = found.iterations;
+            let mut net = |tax| {
+                iterations += 1;
+                economy.preview_net(tax, &mut scratch)
+            };
+            let tax = bounded_tax(found.tax, bound, min_tax, &mut net);
+            (found, tax, iterations)
+        };
+
+        self.tax = tax;
+        let update = self.update();
+        let gini = self.gini();
+        BoundedSolveReport {
+            solve: SolveReport {
+                tax,
+                metric: Metric::Gini,
+                value: gini,
+                target: target_gini,
+                error: gini - target_gini,
+                gini,
+                iterations,
+                clamp: gini_found.clamp,
+                bracketed: gini_found.bracketed,
+                monotonic: gini_found.monotonic,
+                update,
+            },
+            gini_tax: gini_found.tax,
+            bound,
+            conflict: tax != gini_found.tax,
+        }
+    }
+}
@@ -0,0 +1,22 @@
+//! Statistics over fortunes.
+
+/// Computes the Gini coefficient of values sorted in ascending order.
+///
+/// Uses that the sum of absolute differences between all pairs equals
+/// `2 * sum((2 * i - n - 1) * x[i])` for sorted values, with `i` from 1 to `n`.
+pub(crate) fn gini_sorted(sorted: &[f64]) -> f64 {
+    let n = sorted.len() as f64;
+    let mut sum = 0.0;
+    let mut div = 0.0;
+    for (i, &x) in sorted.iter().enumerate() {
+        sum += (2.0 * (i + 1) as f64 - n - 1.0) * x;
+        div += x;
+    }
+    sum / (n * div)
+}
+
+/// Sorts values in ascending order and computes the Gini coefficient.
+pub(crate) fn gini(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    gini_sorted(values)
+}
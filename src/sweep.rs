@@ -0,0 +1,244 @@
+//! Parameter sweeps running many simulations in parallel.
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use {Simulation, SimulationSettings, SimulationSummary};
+
+/// The settings of one simulation in a sweep.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SweepSample {
+    /// Unique id of the sample.
+    pub id: u32,
+    /// The seed of the random transactions.
+    pub seed: u64,
+    /// The fixed tax, or the initial tax when solving.
+    pub tax: f64,
+    /// Solves the tax for a target Gini coefficient when set.
+    pub target_gini: Option<f64>,
+    /// How much to smooth the solved tax, see `Economy::solve`.
+    pub smooth_target: f64,
+    /// The minimum solved tax.
+    pub min_tax: f64,
+    /// The start fortune of players.
+    pub start_fortune: f64,
+    /// The simulation settings.
+    pub simulation: SimulationSettings,
+}
+
+impl SweepSample {
+    /// Runs the simulation until it converges.
+    pub fn run(&self) -> SweepResult {
+        let mut simulation = Simulation::new(
+            self.tax, self.start_fortune, self.simulation, self.seed);
+        let summary = match self.target_gini {
+            Some(target_gini) => simulation.run_with(|economy| {
+                economy.solve(target_gini, self.smooth_target, self.min_tax).update
+            }),
+            None => simulation.run(),
+        };
+        SweepResult {sample: *self, summary}
+    }
+}
+
+/// The result of one simulation in a sweep.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SweepResult {
+    /// The settings.
+    pub sample: SweepSample,
+    /// The smoothed measurements.
+    pub summary: SimulationSummary,
+}
+
+/// Creates samples for every combination of parameters.
+///
+/// An empty list of values uses the value from the base sample.
+/// Samples get increasing ids starting with the id of the base sample,
+/// and the same seed, such that they make the same transactions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SweepGrid {
+    /// The sample to vary.
+    pub base: SweepSample,
+    /// The fixed taxes.
+    pub taxes: Vec<f64>,
+    /// The targets of the Gini solver, `None` for fixed tax.
+    pub target_ginis: Vec<Option<f64>>,
+    /// The start fortunes.
+    pub start_fortunes: Vec<f64>,
+    /// The numbers of players.
+    pub players: Vec<usize>,
+}
+
+impl SweepGrid {
+    /// Creates a new grid with no parameters varied.
+    pub fn new(base: SweepSample) -> SweepGrid {
+        SweepGrid {
+            base,
+            taxes: vec![],
+            target_ginis: vec![],
+            start_fortunes: vec![],
+            players: vec![],
+        }
+    }
+
+    /// Returns the samples of the grid.
+    pub fn samples(&self) -> Vec<SweepSample> {
+        fn or<T: Copy>(values: &[T], base: T) -> Vec<T> {
+            if values.is_empty() { vec![base] } else { values.to_vec() }
+        }
+
+        let base = self.base;
+        let mut samples = vec![];
+        for &tax in &or(&self.taxes, base.tax) {
+            for &target_gini in &or(&self.target_ginis, base.target_gini) {
+                for &start_fortune in &or(&self.start_fortunes, base.start_fortune) {
+                    for &players in &or(&self.players, base.simulation.players) {
+                        let mut sample = base;
+                        sample.id = base.id + samples.len() as u32;
+                        sample.tax = tax;
+                        sample.target_gini = target_gini;
+                        sample.start_fortune = start_fortune;
+                        sample.simulation.players = players;
+                        samples.push(sample);
+                    }
+                }
+            }
+        }
+        samples
+    }
+}
+
+/// Runs samples across threads.
+///
+/// Uses the available parallelism when `threads` is zero.
+/// Returns the results in the same order as the samples.
+pub fn sweep(samples: &[SweepSample], threads: usize) -> Vec<SweepResult> {
+    let threads = if threads == 0 {
+        thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    } else {
+        threads
+    };
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<SweepResult>> = vec![None; samples.len()];
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.min(samples.len())).map(|_| scope.spawn(|| {
+            let mut done = vec![];
+            loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                match samples.get(i) {
+                    Some(sample) => done.push((i, sample.run())),
+                    None => return done,
+                }
+            }
+        })).collect();
+        for worker in workers {
+            for (i, result) in worker.join().unwrap() {
+                results[i] = Some(result);
+            }
+        }
+    });
+    results.into_iter().map(|result| result.unwrap()).collect()
+}
+
+/// Tells how to write sweep results.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SweepFormat {
+    /// Comma separated values with a header row.
+    Csv,
+    /// One JSON object per line.
+    JsonLines,
+}
+
+// The columns written for each result.
+const COLUMNS: [&str; 18] = [
+    "id", "seed", "tax", "target_gini", "smooth_target", "min_tax", "start_fortune",
+    "players", "avg_trans", "trans", "periods", "smooth_fact", "max_measurements",
+    "gini", "mean_tax", "smooth", "measurements", "converged",
+];
+
+enum Cell {
+    Bool(bool),
+    Int(u64),
+    Float(f64),
+    Missing,
+}
+
+impl SweepResult {
+    fn cells(&self) -> [Cell; 18] {
+        let sample = &self.sample;
+        let summary = &self.summary;
+        [
+            Cell::Int(sample.id as u64),
+            Cell::Int(sample.seed),
+            Cell::Float(sample.tax),
+            sample.target_gini.map(Cell::Float).unwrap_or(Cell::Missing),
+            Cell::Float(sample.smooth_target),
+            Cell::Float(sample.min_tax),
+            Cell::Float(sample.start_fortune),
+            Cell::Int(sample.simulation.players as u64),
+            Cell::Float(sample.simulation.avg_trans),
+            Cell::Int(sample.simulation.trans as u64),
+            Cell::Int(sample.simulation.periods as u64),
+            Cell::Float(sample.simulation.smooth_fact),
+            Cell::Int(sample.simulation.max_measurements),
+            Cell::Float(summary.gini),
+            Cell::Float(summary.tax),
+            Cell::Float(summary.smooth),
+            Cell::Int(summary.measurements),
+            Cell::Bool(summary.converged),
+        ]
+    }
+}
+
+impl SweepFormat {
+    /// Writes the header, if any.
+    pub fn write_header<W: Write>(self, w: &mut W) -> io::Result<()> {
+        match self {
+            SweepFormat::Csv => writeln!(w, "{}", COLUMNS.join(",")),
+            SweepFormat::JsonLines => Ok(()),
+        }
+    }
+
+    /// Writes one result row.
+    pub fn write_row<W: Write>(self, w: &mut W, result: &SweepResult) -> io::Result<()> {
+        let cells = result.cells();
+        match self {
+            SweepFormat::Csv => {
+                for (i, cell) in cells.iter().enumerate() {
+                    if i > 0 { write!(w, ",")?; }
+                    match *cell {
+                        Cell::Bool(x) => write!(w, "{}", x)?,
+                        Cell::Int(x) => write!(w, "{}", x)?,
+                        Cell::Float(x) => write!(w, "{}", x)?,
+                        Cell::Missing => {}
+                    }
+                }
+                writeln!(w)
+            }
+            SweepFormat::JsonLines => {
+                write!(w, "{{")?;
+                for (i, (name, cell)) in COLUMNS.iter().zip(&cells).enumerate() {
+                    if i > 0 { write!(w, ",")?; }
+                    write!(w, "\"{}\":", name)?;
+                    match *cell {
+                        Cell::Bool(x) => write!(w, "{}", x)?,
+                        Cell::Int(x) => write!(w, "{}", x)?,
+                        Cell::Float(x) if x.is_finite() => write!(w, "{:?}", x)?,
+                        Cell::Float(_) | Cell::Missing => write!(w, "null")?,
+                    }
+                }
+                writeln!(w, "}}")
+            }
+        }
+    }
+
+    /// Writes the header and all results.
+    pub fn write<W: Write>(self, w: &mut W, results: &[SweepResult]) -> io::Result<()> {
+        self.write_header(w)?;
+        for result in results {
+            self.write_row(w, result)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+//! Treasury funding gameplay from the regulation sink.
+
+use {Amount, TransactionError};
+
+/// Tells what money from the treasury is spent on.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Purpose {
+    /// Rewards for completing quests.
+    QuestReward,
+    /// In-game events.
+    Event,
+    /// Purchases made by non-player characters.
+    NpcPurchase,
+    /// Anything else.
+    Other,
+}
+
+impl Purpose {
+    fn index(self) -> usize {
+        match self {
+            Purpose::QuestReward => 0,
+            Purpose::Event => 1,
+            Purpose::NpcPurchase => 2,
+            Purpose::Other => 3,
+        }
+    }
+}
+
+/// Keeps money collected by the economy, to be spent on gameplay.
+///
+/// Collects money from players leaving with `Departure::Treasury`,
+/// and money burned above the soft limit when `Economy::collect_burns` is set.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Treasury<A = f64> {
+    /// The money available to spend.
+    pub balance: A,
+    /// The total money collected from burns.
+    pub collected_burns: A,
+    /// The total money collected from departing players.
+    pub collected_departures: A,
+    // The total money spent per purpose.
+    spent: [A; 4],
+}
+
+impl<A: Amount> Treasury<A> {
+    /// Creates a new empty treasury.
+    pub fn new() -> Treasury<A> {
+        Treasury {
+            balance: A::zero(),
+            collected_burns: A::zero(),
+            collected_departures: A::zero(),
+            spent: [A::zero(); 4],
+        }
+    }
+
+    /// Returns the total money spent on some purpose.
+    pub fn spent(&self, purpose: Purpose) -> A {
+        self.spent[purpose.index()]
+    }
+
+    /// Returns the total money spent.
+    pub fn total_spent(&self) -> A {
+        self.spent.iter().fold(A::zero(), |sum, &x| sum + x)
+    }
+
+    pub(crate) fn collect_burn(&mut self, amount: A) {
+        self.balance += amount;
+        self.collected_burns += amount;
+    }
+
+    pub(crate) fn collect_departure(&mut self, amount: A) {
+        self.balance += amount;
+        self.collected_departures += amount;
+    }
+
+    /// Checks that an amount can be withdrawn.
+    pub(crate) fn check_withdraw(&self, amount: A) -> Result<(), TransactionError> {
+        if !amount.to_f64().is_finite() || amount < A::zero() {
+            return Err(TransactionError::InvalidAmount(amount.to_f64()));
+        }
+        if amount > self.balance {
+            return Err(TransactionError::InsufficientFunds);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn withdraw(&mut self, amount: A, purpose: Purpose) {
+        self.balance -= amount;
+        self.spent[purpose.index()] += amount;
+    }
+}
+
+impl<A: Amount> Default for Treasury<A> {
+    fn default() -> Treasury<A> {
+        Treasury::new()
+    }
+}
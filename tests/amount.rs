@@ -0,0 +1,56 @@
+extern crate piston_mix_economy;
+
+use std::sync::Arc;
+
+use piston_mix_economy::*;
+
+#[test]
+fn carry_keeps_totals_exact() {
+    for &rounding in &[Rounding::Floor, Rounding::Nearest, Rounding::Ceil] {
+        let mut economy: Economy<i64> = Economy::with_soft_limit(0.0002, 400, 4, 1000);
+        economy.rounding = rounding;
+        let ids: Vec<_> = economy.ids().collect();
+        for &id in &ids[1..] {
+            economy.transaction(id, ids[0], 300).unwrap();
+        }
+
+        // Each update burns and rewards less than one coin in total.
+        let (mut exact_burned, mut exact_distributed) = (0.0, 0.0);
+        let (mut burned, mut distributed) = (0.0, 0.0);
+        for _ in 0..200 {
+            for &p in economy.fortunes() {
+                let p = p as f64;
+                if p >= 1000.0 {
+                    exact_burned += 1000.0 * (p / 1000.0 - 1.0).sqrt() * economy.tax;
+                } else {
+                    exact_distributed += (1000.0 - p) * economy.tax;
+                }
+            }
+            let report = economy.update();
+            burned += report.burned;
+            distributed += report.distributed;
+        }
+        assert!(burned >= 1.0 && distributed >= 1.0, "{:?}", rounding);
+        assert!((burned - exact_burned).abs() <= 1.0, "{:?}", rounding);
+        assert!((distributed - exact_distributed).abs() <= 1.0, "{:?}", rounding);
+    }
+}
+
+#[test]
+fn transaction_overflow() {
+    let mut economy: Economy<i32> = Economy::with_soft_limit(0.0, 2_000_000_000, 2, 1_000_000_000);
+    let ids: Vec<_> = economy.ids().collect();
+    assert_eq!(economy.transaction(ids[0], ids[1], 1_000_000_000),
+        Err(TransactionError::Overflow));
+    assert_eq!(economy.fortunes(), &[2_000_000_000, 2_000_000_000]);
+    economy.transaction(ids[0], ids[1], 100_000_000).unwrap();
+}
+
+#[test]
+fn burn_is_capped_at_fortune() {
+    let mut economy: Economy<u32> = Economy::with_soft_limit(1.0, 20, 2, 10);
+    economy.burn_curve = Arc::new(BracketBurn {brackets: vec![(0.0, 5.0)]});
+    let report = economy.update();
+    assert_eq!(report.burned, 40.0);
+    assert_eq!(report.above_limit, 2);
+}
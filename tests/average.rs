@@ -0,0 +1,39 @@
+extern crate piston_mix_economy;
+
+use piston_mix_economy::{ConvergingAverage, WindowedMean};
+
+#[test]
+fn converging_average_matches_smoothing_loop() {
+    let values: Vec<f64> = (0..100).map(|i| (i as f64 * 0.7).sin()).collect();
+    let smooth_fact = 0.9;
+    let mut average = ConvergingAverage::new(smooth_fact);
+    let mut smooth_value = 0.0;
+    let mut smooth = 1.0;
+    for &x in &values {
+        assert_eq!(average.weight(), smooth);
+        smooth_value += (x - smooth_value) * smooth;
+        smooth *= smooth_fact;
+        assert_eq!(average.add(x), smooth_value);
+        if smooth < 1.0 - smooth_fact { break; }
+        assert!(!average.converged());
+    }
+    assert!(average.converged());
+    assert_eq!(average.measurements(), 22);
+}
+
+#[test]
+fn windowed_mean() {
+    let mut mean = WindowedMean::new(4);
+    assert_eq!(mean.mean(), None);
+    mean.add(10.0);
+    assert_eq!(mean.standard_error(), None);
+    for &x in &[1.0, 2.0, 3.0, 4.0] {
+        mean.add(x);
+    }
+    assert!(mean.is_full());
+    assert_eq!(mean.len(), 4);
+    assert_eq!(mean.mean(), Some(2.5));
+    // Sample variance is 5/3, divided by 4 values.
+    let error = mean.standard_error().unwrap();
+    assert!((error - (5.0f64 / 12.0).sqrt()).abs() < 1e-12);
+}
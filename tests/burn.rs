@@ -0,0 +1,32 @@
+extern crate piston_mix_economy;
+
+use piston_mix_economy::{BracketBurn, BurnCurve};
+
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-12, "{} vs {}", a, b);
+}
+
+#[test]
+fn bracket_marginal_rates() {
+    let curve = BracketBurn {brackets: vec![(0.5, 0.1), (1.0, 0.5), (2.0, 1.0)]};
+    // Below the first threshold.
+    assert_eq!(curve.burn(0.0), 0.0);
+    assert_eq!(curve.burn(0.5), 0.0);
+    // Inside a bracket.
+    assert_close(curve.burn(0.8), 0.3 * 0.1);
+    // Across several brackets.
+    assert_close(curve.burn(1.5), 0.5 * 0.1 + 0.5 * 0.5);
+    assert_close(curve.burn(3.0), 0.5 * 0.1 + 1.0 * 0.5 + 1.0 * 1.0);
+
+    let unsorted = BracketBurn {brackets: vec![(2.0, 1.0), (0.5, 0.1), (1.0, 0.5)]};
+    for &excess in &[0.0, 0.5, 0.8, 1.0, 1.5, 2.0, 3.0] {
+        assert_close(unsorted.burn(excess), curve.burn(excess));
+    }
+}
+
+#[test]
+fn bracket_from_zero_is_linear() {
+    let curve = BracketBurn {brackets: vec![(0.0, 0.25)]};
+    assert_close(curve.burn(2.0), 0.5);
+    assert_eq!(BracketBurn {brackets: vec![]}.burn(2.0), 0.0);
+}
@@ -0,0 +1,38 @@
+//! Helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use piston_mix_economy::{Amount, Economy};
+
+/// Returns a random generator with a fixed seed, such that tests are reproducible.
+pub fn rng() -> StdRng {
+    StdRng::seed_from_u64(12345)
+}
+
+/// Makes transactions of a fixed amount between random players.
+///
+/// Rejected transactions are ignored.
+pub fn random_transactions<A: Amount>(
+    economy: &mut Economy<A>,
+    rng: &mut StdRng,
+    amount: A,
+    transactions: usize,
+) {
+    let ids: Vec<_> = economy.ids().collect();
+    for _ in 0..transactions {
+        let from = ids[rng.gen_range(0..ids.len())];
+        let to = ids[rng.gen_range(0..ids.len())];
+        let _ = economy.transaction(from, to, amount);
+    }
+}
+
+/// Creates an economy of 100 players without tax,
+/// with fortunes spread out by random transactions.
+pub fn economy<A: Amount>(start: A, amount: A, limit: A) -> Economy<A> {
+    let mut economy = Economy::with_soft_limit(0.0, start, 100, limit);
+    random_transactions(&mut economy, &mut rng(), amount, 10_000);
+    economy
+}
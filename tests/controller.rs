@@ -0,0 +1,70 @@
+extern crate piston_mix_economy;
+
+use piston_mix_economy::TaxController;
+
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-12, "{} vs {}", a, b);
+}
+
+#[test]
+fn max_step_limits_change() {
+    let mut controller = TaxController::new(0.3);
+    controller.kp = 1.0;
+    assert_eq!(controller.next_tax(1.0, 0.2), 0.2 + 0.01);
+    assert_eq!(controller.next_tax(0.0, 0.2), 0.2 - 0.01);
+    controller.max_step = 0.05;
+    assert_eq!(controller.next_tax(1.0, 0.2), 0.2 + 0.05);
+}
+
+#[test]
+fn tax_is_clamped() {
+    let mut controller = TaxController::new(0.3);
+    controller.max_step = 1.0;
+    controller.min_tax = 0.05;
+    controller.max_tax = 0.1;
+    assert_eq!(controller.next_tax(1.0, 0.07), 0.1);
+    controller.reset();
+    assert_eq!(controller.next_tax(0.0, 0.07), 0.05);
+    controller.reset();
+    // Within limits, the output is used as it is.
+    let output = controller.kp * 0.1 + controller.ki * 0.1;
+    assert_close(controller.next_tax(0.4, 0.07), output);
+}
+
+#[test]
+fn integral_does_not_wind_up() {
+    let mut controller = TaxController::new(0.3);
+    controller.kp = 0.0;
+    controller.ki = 1.0;
+    let mut tax = 0.0;
+    // The error pushes the tax into the step limit every update.
+    for _ in 0..20 {
+        let next = controller.next_tax(0.8, tax);
+        assert_eq!(next, tax + 0.01);
+        tax = next;
+    }
+    // Without windup, the tax falls as soon as the error is gone.
+    assert_eq!(controller.next_tax(0.3, tax), tax - 0.01);
+
+    // Integrates while the output is not limited.
+    let mut controller = TaxController::new(0.3);
+    controller.kp = 0.0;
+    controller.ki = 1.0;
+    assert_close(controller.next_tax(0.305, 0.0), 0.005);
+    assert_close(controller.next_tax(0.305, 0.005), 0.01);
+}
+
+#[test]
+fn reset_forgets_history() {
+    let mut controller = TaxController::new(0.3);
+    controller.kd = 0.1;
+    controller.max_step = 1.0;
+    let mut fresh = controller.clone();
+    for _ in 0..10 {
+        controller.next_tax(0.35, 0.1);
+    }
+    assert!(controller.next_tax(0.32, 0.1) != fresh.clone().next_tax(0.32, 0.1));
+    controller.reset();
+    assert_eq!(controller.next_tax(0.32, 0.1), fresh.next_tax(0.32, 0.1));
+    assert_eq!(controller.next_tax(0.31, 0.1), fresh.next_tax(0.31, 0.1));
+}
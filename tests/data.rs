@@ -0,0 +1,84 @@
+extern crate piston_mix_economy;
+
+use piston_mix_economy::{ComparisonRecord, DataError, RecordFile, SolverRecord};
+
+#[test]
+fn read_and_write_smooth() {
+    let source = include_str!("../data/smooth.dyon");
+    let file: RecordFile<ComparisonRecord> = source.parse().unwrap();
+    assert_eq!(file.records.len(), 100);
+    assert!(file.header.starts_with("/*"));
+    let first = &file.records[0];
+    assert_eq!(first.comments[0], "// This repetition of the same program, but does not give");
+    assert!(first.record.needs_rerun);
+    assert_eq!(first.record.diff_1_vs_2, Some(5.0));
+    assert_eq!(first.record.smooth, 9.02474425092397E-18);
+    assert_eq!(first.record.rng, "thread_rng");
+
+    // Only the formatting of numbers changes.
+    let written = file.to_string();
+    assert_eq!(written.lines().count(), source.lines().count());
+    assert_eq!(written.parse::<RecordFile<ComparisonRecord>>().unwrap(), file);
+}
+
+#[test]
+fn read_and_write_solver_bug() {
+    let source = include_str!("../data/solver-bug.dyon");
+    let file: RecordFile<SolverRecord> = source.parse().unwrap();
+    assert_eq!(file.records.len(), 21);
+    assert_eq!(file.records[0].record.name, "Mix Gini 0.05");
+    assert_eq!(file.records[0].record.cliff, 0.19);
+    assert_eq!(file.to_string(), source);
+}
+
+#[test]
+fn report_errors() {
+    let source = "[\n    {\n        name: \"a\",\n        start_fortune: 0.1,\n    },\n]";
+    let error = source.parse::<RecordFile<SolverRecord>>().unwrap_err();
+    assert_eq!(error, DataError {line: 2, message: "Missing field `sh`".into()});
+
+    let error = "[\n    {\n        sh: 0.1 cliff: 0.2,\n".parse::<RecordFile<SolverRecord>>()
+        .unwrap_err();
+    assert_eq!(error.line, 3);
+}
+
+#[test]
+fn rerun_flagged_records() {
+    let source = "// Test\n[\n    {\n        id: 1,\n        needs_rerun: true,\n        \
+        tax_1: 0.1,\n        tax_2: 0,\n        start_fortune: 0.2,\n        players: 20,\n        \
+        avg_trans: 0.1,\n        trans: 100,\n        periods: 2,\n        economy_1: 0,\n        \
+        economy_2: 0,\n        smooth: 1,\n        rng: \"os_rng\",\n        smooth_fact: 0.8,\n    },\n]";
+    let mut file: RecordFile<ComparisonRecord> = source.parse().unwrap();
+    let mut other = file.clone();
+    other.records[0].record.needs_rerun = false;
+
+    assert_eq!(file.rerun(2), 1);
+    assert_eq!(other.rerun(2), 0);
+    let record = &file.records[0].record;
+    assert!(!record.needs_rerun);
+    assert_eq!(record.seed, Some(1));
+    assert!(record.economy_1 > 0.0 && record.economy_2 > record.economy_1);
+    assert!(record.smooth < 0.2);
+
+    // Reruns are reproducible.
+    let mut again: RecordFile<ComparisonRecord> = source.parse().unwrap();
+    again.rerun(1);
+    assert_eq!(again, file);
+    assert_eq!(file.to_string().parse::<RecordFile<ComparisonRecord>>().unwrap(), file);
+}
+
+#[test]
+fn keep_comments_inside_objects() {
+    let source = "[\n    {\n        // Name\n        name: \"a\",\n        start_fortune: 0.1,\n        \
+        /* Two */ sh: 0.2,\n        cliff: 0.3,\n        // Last\n    },\n]\n";
+    let file: RecordFile<SolverRecord> = source.parse().unwrap();
+    assert_eq!(file.records[0].field_comments, vec![
+        ("name".to_string(), vec!["// Name".to_string()]),
+        ("sh".to_string(), vec!["/* Two */".to_string()]),
+        (String::new(), vec!["// Last".to_string()]),
+    ]);
+    let written = file.to_string();
+    assert_eq!(written, "[\n    {\n        // Name\n        name: \"a\",\n        start_fortune: 0.1,\n        \
+        /* Two */\n        sh: 0.2,\n        cliff: 0.3,\n        // Last\n    },\n]\n");
+    assert_eq!(written.parse::<RecordFile<SolverRecord>>().unwrap(), file);
+}
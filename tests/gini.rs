@@ -0,0 +1,50 @@
+extern crate piston_mix_economy;
+extern crate rand;
+
+mod common;
+
+use rand::Rng;
+
+use piston_mix_economy::Economy;
+
+fn random_economy(players: usize) -> Economy {
+    let mut economy = Economy::new(0.1, 0.25, players);
+    let ids: Vec<_> = economy.ids().collect();
+    let mut rng = common::rng();
+    for i in 0..players * 50 {
+        let from = ids[rng.gen_range(0..players)];
+        let to = ids[rng.gen_range(0..players)];
+        let _ = economy.transaction(from, to, 0.01 * (i % 7) as f64);
+        if i % players == 0 { economy.update(); }
+    }
+    economy
+}
+
+#[test]
+fn gini_matches_quadratic() {
+    for &players in &[1, 2, 3, 10, 100, 1000] {
+        let economy = random_economy(players);
+        let gini = economy.gini();
+        let reference = economy.gini_quadratic();
+        assert!((gini - reference).abs() < 1e-12, "{} vs {}", gini, reference);
+    }
+}
+
+#[test]
+fn gini_equal_fortunes() {
+    let economy = Economy::new(0.1, 0.25, 100);
+    assert_eq!(economy.gini(), 0.0);
+    assert_eq!(economy.gini_quadratic(), 0.0);
+}
+
+#[test]
+fn metrics_without_players() {
+    use piston_mix_economy::Metric;
+
+    let economy = Economy::new(0.1, 0.25, 0);
+    assert!(economy.gini().is_nan());
+    for &metric in &[Metric::Gini, Metric::Mean, Metric::Median, Metric::TopShare(0.1)] {
+        assert!(economy.metric(metric).is_nan(), "{:?}", metric);
+    }
+    assert_eq!(economy.metric(Metric::TotalSupply), 0.0);
+}
@@ -0,0 +1,77 @@
+extern crate piston_mix_economy;
+extern crate rand;
+
+mod common;
+
+use rand::Rng;
+
+use piston_mix_economy::{Amount, Departure, Economy, Event, Purpose, ReplayError};
+
+fn run<A: Amount>(start: A, amount: A, limit: A) {
+    let mut economy = Economy::with_soft_limit(0.05, start, 50, limit);
+    economy.collect_burns = true;
+    economy.record_ledger = true;
+    let snapshot = economy.clone();
+
+    let mut rng = common::rng();
+    for k in 0..20 {
+        common::random_transactions(&mut economy, &mut rng, amount, 200);
+        let ids: Vec<_> = economy.ids().collect();
+        if k % 5 == 4 {
+            economy.remove_player(ids[rng.gen_range(0..ids.len())], Departure::Treasury);
+            economy.remove_player(ids[rng.gen_range(0..ids.len())], Departure::Redistribute);
+            economy.add_player();
+            economy.tax += 0.01;
+        }
+        economy.update();
+        let _ = economy.spend_from_treasury(amount, Purpose::Event);
+        let _ = economy.pay_from_treasury(ids[0], amount, Purpose::QuestReward);
+    }
+
+    let entries = economy.ledger().entries().to_vec();
+    for pair in entries.windows(2) {
+        assert_eq!(pair[1].sequence, pair[0].sequence + 1);
+    }
+    assert!(entries.iter().any(|e| matches!(e.event, Event::TaxChange {..})));
+
+    let mut replayed = snapshot.replay(&entries).unwrap();
+    assert_eq!(replayed.fortunes(), economy.fortunes());
+    assert!(replayed.ids().eq(economy.ids()));
+    assert_eq!(replayed.tax, economy.tax);
+    assert_eq!(replayed.treasury, economy.treasury);
+    assert_eq!(replayed.ledger(), economy.ledger());
+    // Carries from rounding are restored.
+    assert_eq!(replayed.update(), economy.update());
+    assert_eq!(replayed.fortunes(), economy.fortunes());
+}
+
+#[test]
+fn replay_reproduces_economy() {
+    run(0.5, 0.03, 1.0);
+    run(500i64, 30, 1000);
+}
+
+#[test]
+fn replay_from_later_snapshot() {
+    let mut economy = Economy::new(0.1, 0.5, 10);
+    economy.record_ledger = true;
+    let ids: Vec<_> = economy.ids().collect();
+    economy.transaction(ids[0], ids[1], 0.3).unwrap();
+    economy.update();
+    let snapshot = economy.clone();
+    economy.transaction(ids[1], ids[2], 0.5).unwrap();
+    economy.update();
+
+    let entries = economy.take_ledger_entries();
+    assert!(economy.ledger().entries().is_empty());
+    let replayed = snapshot.replay(&entries).unwrap();
+    assert_eq!(replayed.fortunes(), economy.fortunes());
+
+    let start = snapshot.ledger().next_sequence() as usize;
+    let mut gap = entries.clone();
+    gap.remove(start + 1);
+    assert_eq!(snapshot.replay(&gap).err(), Some(ReplayError::Sequence {
+        expected: start as u64 + 1,
+        found: start as u64 + 2,
+    }));
+}
@@ -0,0 +1,75 @@
+extern crate piston_mix_economy;
+
+use piston_mix_economy::{Departure, Economy, Metric};
+
+fn economy() -> Economy<i64> {
+    let mut economy = Economy::with_soft_limit(0.0, 100, 5, 1000);
+    let ids: Vec<_> = economy.ids().collect();
+    for (i, &id) in ids[..4].iter().enumerate() {
+        economy.transaction(id, ids[4], 10 * i as i64).unwrap();
+    }
+    economy
+}
+
+#[test]
+fn ids_stay_valid_after_removal() {
+    let mut economy = economy();
+    let ids: Vec<_> = economy.ids().collect();
+    let fortunes: Vec<_> = ids.iter().map(|&id| economy.fortune(id).unwrap()).collect();
+
+    assert_eq!(economy.remove_player(ids[1], Departure::Burn), Some(fortunes[1]));
+    assert_eq!(economy.len(), 4);
+    assert!(!economy.contains(ids[1]));
+    assert_eq!(economy.fortune(ids[1]), None);
+    assert_eq!(economy.remove_player(ids[1], Departure::Burn), None);
+    for i in &[0, 2, 3, 4] {
+        assert_eq!(economy.fortune(ids[*i]), Some(fortunes[*i]));
+    }
+    // The last player took the place of the removed one.
+    assert!(economy.ids().eq(vec![ids[0], ids[4], ids[2], ids[3]]));
+    for (id, &fortune) in economy.ids().zip(economy.fortunes()) {
+        assert_eq!(economy.fortune(id), Some(fortune));
+    }
+
+    // The slot is reused with a new generation.
+    let id = economy.add_player();
+    assert!(id != ids[1]);
+    assert_eq!(economy.fortune(id), Some(100));
+    assert_eq!(economy.fortune(ids[1]), None);
+    economy.remove_player(ids[0], Departure::Burn);
+    assert_eq!(economy.fortune(id), Some(100));
+    assert_eq!(economy.fortune(ids[4]), Some(fortunes[4]));
+}
+
+#[test]
+fn departures() {
+    let mut economy = economy();
+    let ids: Vec<_> = economy.ids().collect();
+    let supply = economy.metric(Metric::TotalSupply);
+    let fortune = economy.remove_player(ids[0], Departure::Burn).unwrap();
+    assert_eq!(economy.metric(Metric::TotalSupply), supply - fortune as f64);
+    assert_eq!(economy.treasury.balance, 0);
+
+    let mut economy = self::economy();
+    let before: Vec<_> = ids.iter().map(|&id| economy.fortune(id).unwrap()).collect();
+    let fortune = economy.remove_player(ids[0], Departure::Redistribute).unwrap();
+    assert_eq!(fortune, 100);
+    assert_eq!(economy.metric(Metric::TotalSupply), supply);
+    for i in 1..5 {
+        assert_eq!(economy.fortune(ids[i]), Some(before[i] + 25));
+    }
+
+    let mut economy = self::economy();
+    let fortune = economy.remove_player(ids[0], Departure::Treasury).unwrap();
+    assert_eq!(economy.treasury.balance, fortune);
+    assert_eq!(economy.metric(Metric::TotalSupply), supply - fortune as f64);
+}
+
+#[test]
+fn redistribute_last_player() {
+    let mut economy: Economy = Economy::new(0.1, 0.5, 1);
+    let id = economy.ids().next().unwrap();
+    assert_eq!(economy.remove_player(id, Departure::Redistribute), Some(0.5));
+    assert!(economy.is_empty());
+    economy.update();
+}
@@ -0,0 +1,176 @@
+extern crate piston_mix_economy;
+extern crate rand;
+
+mod common;
+
+use common::economy;
+use piston_mix_economy::{Economy, Scratch};
+
+/// The solver as it was before previews, cloning the economy for each candidate tax.
+fn solve_by_clone<A: piston_mix_economy::Amount>(
+    economy: &Economy<A>,
+    target_gini: f64,
+    smooth_target: f64,
+) -> f64 {
+    let mut tax = 0.0;
+    let mut step = 0.5;
+    loop {
+        if tax > 1.0 { break; }
+        let mut copy = economy.clone();
+        copy.tax = tax;
+        copy.update();
+        if target_gini - copy.gini() > 0.0 { tax -= step; } else { tax += step; }
+        step *= smooth_target;
+        if step < 0.0001 { break; }
+    }
+    tax.clamp(0.001, 1.0)
+}
+
+#[test]
+fn preview_matches_update() {
+    let economy = economy(0.25, 0.03, 1.0);
+    let mut scratch = Scratch::new();
+    for &tax in &[0.0, 0.1, 0.5, 1.0] {
+        let mut copy = economy.clone();
+        copy.tax = tax;
+        copy.update();
+        assert_eq!(economy.preview_update(tax), copy.fortunes());
+        assert_eq!(economy.preview_gini(tax, &mut scratch), copy.gini());
+    }
+}
+
+#[test]
+fn solve_matches_clone() {
+    for &target in &[0.05, 0.1, 0.2, 0.3, 0.5] {
+        let mut a = economy(0.25, 0.03, 1.0);
+        let expected = solve_by_clone(&a, target, 0.9);
+        let report = a.solve(target, 0.9, 0.001);
+        assert_eq!(a.tax, expected);
+        assert_eq!(report.tax, expected);
+        assert_eq!(report.gini, a.gini());
+
+        let mut b = economy(2500i64, 300, 10000);
+        let expected = solve_by_clone(&b, target, 0.9);
+        b.solve(target, 0.9, 0.001);
+        assert_eq!(b.tax, expected);
+    }
+}
+
+#[test]
+fn update_report_matches_supply() {
+    use piston_mix_economy::Metric;
+
+    let mut economy = economy(2500i64, 300, 10000);
+    economy.tax = 0.2;
+    let above = economy.fortunes().iter().filter(|&&p| p >= 10000).count();
+    let before = economy.metric(Metric::TotalSupply);
+    let report = economy.update();
+    let after = economy.metric(Metric::TotalSupply);
+    assert_eq!(report.net, after - before);
+    assert_eq!(report.net, report.distributed - report.burned);
+    assert_eq!(report.above_limit, above);
+    assert!(report.below_limit >= economy.len() - above);
+}
+
+#[test]
+fn balanced_budget_conserves_supply() {
+    use piston_mix_economy::{Budget, Metric};
+
+    let mut economy = economy(2500i64, 3000, 10000);
+    economy.budget = Budget::Balanced;
+    economy.tax = 0.5;
+    let before = economy.metric(Metric::TotalSupply);
+    for _ in 0..10 {
+        let report = economy.update();
+        assert_eq!(report.burned, report.distributed);
+        assert_eq!(report.net, 0.0);
+    }
+    assert_eq!(economy.metric(Metric::TotalSupply), before);
+}
+
+#[test]
+fn projection_matches_update() {
+    let mut economy = economy(500i64, 60, 1000);
+    economy.tax = 0.1;
+    let before = economy.fortunes().to_vec();
+    let projections = economy.projections(&mut Scratch::new());
+    let first = economy.ids().next().unwrap();
+    assert_eq!(economy.projection(first), Some(projections[0]));
+    assert_eq!(economy.fortunes(), &before[..]);
+    assert!(projections.iter().any(|p| p.burn > 0));
+
+    economy.update();
+    for (i, p) in projections.iter().enumerate() {
+        assert_eq!(p.fortune, before[i]);
+        assert_eq!(p.next_fortune, economy.fortunes()[i]);
+        assert_eq!((p.next_fortune - p.fortune) as f64, p.net());
+    }
+}
+
+#[test]
+fn audit_explains_update() {
+    let mut economy = economy(500i64, 60, 1000);
+    economy.tax = 0.1;
+    economy.update();
+    assert!(economy.audits().is_empty());
+
+    economy.audit_updates = true;
+    let before = economy.fortunes().to_vec();
+    let report = economy.update();
+    assert_eq!(economy.audits().len(), economy.len());
+    assert_eq!(economy.audits().iter().filter(|a| a.burned).count(), report.above_limit);
+    assert_eq!(economy.audits().iter().filter(|a| a.rewarded).count(), report.below_limit);
+    for (i, id) in economy.ids().enumerate() {
+        let audit = economy.audit(id).unwrap();
+        assert_eq!(audit.tax, 0.1);
+        assert_eq!(audit.before, before[i]);
+        assert_eq!(audit.after, economy.fortunes()[i]);
+        assert_eq!(audit.after, audit.before - audit.burn + audit.reward);
+        assert_eq!(audit.rewarded, audit.weight > 0.0);
+        assert_eq!(audit.weighted_fortune, (audit.before - audit.burn) as f64 / 1000.0);
+        if audit.floored {
+            assert_eq!(audit.weight, 0.5f64.sqrt());
+        } else if audit.rewarded {
+            assert_eq!(audit.weight, audit.weighted_fortune.sqrt());
+        }
+    }
+    assert!(economy.audits().iter().any(|a| a.floored));
+    assert!(economy.audits().iter().any(|a| a.rewarded && !a.floored));
+}
+
+#[test]
+fn balanced_budget_without_poor_players() {
+    use piston_mix_economy::Budget;
+
+    let mut economy = Economy::new(0.5, 2.0, 3);
+    economy.budget = Budget::Balanced;
+    economy.audit_updates = true;
+    let report = economy.update();
+    assert_eq!(report.above_limit, 3);
+    assert_eq!(report.below_limit, 0);
+    assert_eq!(report.distributed, report.burned);
+    assert!(economy.audits().iter().all(|a| a.burned && a.rewarded));
+}
+
+#[test]
+fn audit_lookup_after_players_change() {
+    use piston_mix_economy::Departure;
+
+    let mut economy = economy(500i64, 60, 1000);
+    economy.tax = 0.1;
+    economy.audit_updates = true;
+    economy.update();
+    let ids: Vec<_> = economy.ids().collect();
+    let removed = economy.remove_player(ids[10], Departure::Burn);
+    let joined = economy.add_player();
+    // The audits still describe the last update.
+    assert_eq!(economy.audit(ids[10]).map(|a| a.after), removed);
+    assert_eq!(economy.audit(joined), None);
+    for (i, &id) in ids.iter().enumerate() {
+        assert_eq!(economy.audit(id), Some(&economy.audits()[i]));
+    }
+
+    economy.update();
+    assert_eq!(economy.audit(ids[10]), None);
+    assert_eq!(economy.audit(joined).map(|a| a.player), Some(joined));
+}
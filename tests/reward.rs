@@ -0,0 +1,40 @@
+extern crate piston_mix_economy;
+
+use piston_mix_economy::{CappedReward, FlatReward, LinearReward, RewardWeighting, SqrtReward};
+
+#[test]
+fn flat_reward() {
+    for &fortune in &[0.0, 0.1, 0.5, 0.99] {
+        assert_eq!(FlatReward.weight(fortune, 0.25), 1.0);
+    }
+}
+
+#[test]
+fn linear_reward() {
+    assert_eq!(LinearReward.weight(0.5, 0.25), 0.5);
+    assert_eq!(LinearReward.weight(0.25, 0.25), 0.25);
+    // Poorer players use the start fortune.
+    assert_eq!(LinearReward.weight(0.1, 0.25), 0.25);
+    assert_eq!(LinearReward.weight(0.0, 0.25), 0.25);
+}
+
+#[test]
+fn sqrt_reward() {
+    assert_eq!(SqrtReward.weight(0.64, 0.25), 0.8);
+    assert_eq!(SqrtReward.weight(0.1, 0.25), 0.5);
+}
+
+#[test]
+fn capped_reward() {
+    let capped = CappedReward {cap: 0.49};
+    assert_eq!(capped.weight(0.36, 0.25), 0.6);
+    // Richer players use the cap.
+    assert_eq!(capped.weight(0.49, 0.25), 0.7);
+    assert_eq!(capped.weight(0.9, 0.25), 0.7);
+    // Poorer players use the start fortune.
+    assert_eq!(capped.weight(0.1, 0.25), 0.5);
+    // A cap below the start fortune gives everyone the same weight.
+    let low = CappedReward {cap: 0.04};
+    assert_eq!(low.weight(0.0, 0.25), 0.5);
+    assert_eq!(low.weight(0.9, 0.25), 0.5);
+}
@@ -0,0 +1,86 @@
+extern crate piston_mix_economy;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+
+use piston_mix_economy::*;
+
+fn scenario(mode: RegulationMode) -> Scenario {
+    let mut scenario = Scenario::default();
+    scenario.economy.players = 20;
+    scenario.transactions.seed = 3;
+    scenario.transactions.trans = 100;
+    scenario.regulation.mode = mode;
+    scenario.regulation.min_tax = 0.001;
+    scenario.duration.updates = 50;
+    scenario.duration.sample_every = 5;
+    scenario
+}
+
+#[test]
+fn run_is_reproducible() {
+    for &mode in &[RegulationMode::Fixed, RegulationMode::Solve,
+                   RegulationMode::Bisection, RegulationMode::Controller] {
+        let output = scenario(mode).run();
+        assert_eq!(output, scenario(mode).run());
+        assert_eq!(output.summary.updates, 50);
+        assert_eq!(output.series.len(), 10);
+        assert_eq!(output.series[9].update, 50);
+        assert_eq!(output.series[9].gini, output.summary.final_gini);
+        assert_eq!(output.series[9].tax, output.summary.final_tax);
+    }
+    let fixed = scenario(RegulationMode::Fixed).run();
+    assert!(fixed.series.iter().all(|row| row.tax == 0.0));
+    let regulated = scenario(RegulationMode::Controller).run();
+    assert!(regulated.series.iter().any(|row| row.tax > 0.0));
+}
+
+#[test]
+fn run_until_converged() {
+    let mut scenario = scenario(RegulationMode::Controller);
+    scenario.duration.mode = DurationMode::Converge;
+    scenario.duration.periods = 2;
+    scenario.duration.smooth_fact = 0.8;
+    scenario.duration.updates = 100_000;
+    let output = scenario.run();
+    assert!(output.summary.converged);
+    assert!(output.summary.updates < 100_000);
+    assert_eq!(output.series.len() as u32, output.summary.updates / 5);
+
+    // Never converges, but stops at the most updates.
+    scenario.duration.smooth_fact = 1.0;
+    scenario.duration.updates = 50;
+    let output = scenario.run();
+    assert!(!output.summary.converged);
+    assert_eq!(output.summary.updates, 50);
+}
+
+#[test]
+fn series_output() {
+    let output = scenario(RegulationMode::Fixed).run();
+    let mut csv = vec![];
+    output.write_series(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 11);
+    assert_eq!(lines[0], "update,tax,gini,supply,burned,distributed,above_limit,below_limit");
+    assert!(lines[1].starts_with("5,0,"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn read_scenario() {
+    let json = r#"{
+        "economy": {"players": 20, "burn_curve": {"bracket": [[0.0, 0.1], [1.0, 0.5]]}},
+        "transactions": {"seed": 3, "trans": 100},
+        "regulation": {"mode": "controller", "min_tax": 0.001},
+        "duration": {"updates": 50, "sample_every": 5}
+    }"#;
+    let read: Scenario = serde_json::from_str(json).unwrap();
+    let mut expected = scenario(RegulationMode::Controller);
+    expected.economy.burn_curve = BurnConfig::Bracket(vec![(0.0, 0.1), (1.0, 0.5)]);
+    assert_eq!(read, expected);
+
+    let empty: Scenario = serde_json::from_str("{}").unwrap();
+    assert_eq!(empty, Scenario::default());
+    assert!(serde_json::from_str::<Scenario>(r#"{"economy": {"tax_rate": 0.1}}"#).is_err());
+}
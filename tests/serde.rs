@@ -0,0 +1,116 @@
+#![cfg(feature = "serde")]
+
+extern crate piston_mix_economy;
+extern crate rand;
+extern crate serde_json;
+
+mod common;
+
+use std::sync::Arc;
+
+use piston_mix_economy::*;
+
+/// An economy using every saved feature, unlike `common::economy`.
+fn configured_economy<A: Amount>(start: A, amount: A, limit: A) -> Economy<A> {
+    let mut economy = Economy::with_soft_limit(0.05, start, 50, limit);
+    economy.burn_curve = Arc::new(BracketBurn {brackets: vec![(0.0, 0.1), (1.0, 0.5)]});
+    economy.reward_weighting = Arc::new(CappedReward {cap: 0.8});
+    economy.collect_burns = true;
+    economy.audit_updates = true;
+    economy.record_ledger = true;
+    let ids: Vec<_> = economy.ids().collect();
+    let mut rng = common::rng();
+    for _ in 0..10 {
+        common::random_transactions(&mut economy, &mut rng, amount, 200);
+        economy.update();
+    }
+    economy.remove_player(ids[3], Departure::Treasury);
+    economy
+}
+
+fn round_trip<A>(economy: &Economy<A>) -> Economy<A>
+    where A: Amount + serde::Serialize + serde::de::DeserializeOwned
+{
+    let json = serde_json::to_string(economy).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+fn check<A>(mut economy: Economy<A>)
+    where A: Amount + serde::Serialize + serde::de::DeserializeOwned
+{
+    let mut restored = round_trip(&economy);
+    assert_eq!(restored.fortunes(), economy.fortunes());
+    assert!(restored.ids().eq(economy.ids()));
+    assert_eq!(restored.treasury, economy.treasury);
+    assert_eq!(restored.ledger(), economy.ledger());
+    assert_eq!(restored.audits(), economy.audits());
+    assert_eq!(restored.add_player(), economy.add_player());
+    for _ in 0..5 {
+        assert_eq!(restored.update(), economy.update());
+        assert_eq!(restored.fortunes(), economy.fortunes());
+    }
+    let strategy = SolverStrategy::Bisection {tolerance: 1e-6, max_iterations: 64};
+    for _ in 0..5 {
+        let a = restored.solve_with(0.2, strategy, 0.0);
+        let b = economy.solve_with(0.2, strategy, 0.0);
+        assert_eq!(a.tax, b.tax);
+        assert_eq!(a.update, b.update);
+        assert_eq!(restored.fortunes(), economy.fortunes());
+    }
+}
+
+#[test]
+fn round_trip_gives_identical_updates() {
+    check(configured_economy(0.5, 0.03, 1.0));
+    check(configured_economy(500i64, 30, 1000));
+}
+
+#[test]
+fn snapshot_is_versioned() {
+    let economy = Economy::new(0.1, 0.5, 3);
+    let mut value = serde_json::to_value(&economy).unwrap();
+    assert_eq!(value["version"], SNAPSHOT_VERSION);
+
+    value["version"] = (SNAPSHOT_VERSION + 1).into();
+    assert!(serde_json::from_value::<Economy>(value).is_err());
+}
+
+#[test]
+fn custom_curve_is_not_saved() {
+    struct Custom;
+    impl BurnCurve for Custom {
+        fn burn(&self, excess: f64) -> f64 { excess }
+    }
+
+    let mut economy = Economy::new(0.1, 0.5, 3);
+    economy.burn_curve = Arc::new(Custom);
+    assert!(serde_json::to_string(&economy).is_err());
+}
+
+#[test]
+fn inconsistent_snapshot_is_rejected() {
+    let mut economy = configured_economy(0.5, 0.03, 1.0);
+    economy.update();
+    let value = serde_json::to_value(&economy).unwrap();
+    assert!(serde_json::from_value::<Economy>(value.clone()).is_ok());
+
+    let mut truncated = value.clone();
+    truncated["players"].as_array_mut().unwrap().pop();
+    assert!(serde_json::from_value::<Economy>(truncated).is_err());
+
+    let mut moved = value.clone();
+    moved["slots"]["slots"][0]["dense"] = 1.into();
+    assert!(serde_json::from_value::<Economy>(moved).is_err());
+
+    let mut owners = value.clone();
+    owners["slots"]["owners"].as_array_mut().unwrap().swap(0, 1);
+    assert!(serde_json::from_value::<Economy>(owners).is_err());
+
+    let mut free = value.clone();
+    free["slots"]["free"] = vec![0].into();
+    assert!(serde_json::from_value::<Economy>(free).is_err());
+
+    let mut audit = value;
+    audit["audits"][0]["player"]["index"] = 1_000_000.into();
+    assert!(serde_json::from_value::<Economy>(audit).is_err());
+}
@@ -0,0 +1,55 @@
+extern crate piston_mix_economy;
+
+use piston_mix_economy::{Simulation, SimulationSettings};
+
+fn settings() -> SimulationSettings {
+    SimulationSettings {
+        players: 50,
+        avg_trans: 0.1,
+        trans: 200,
+        periods: 5,
+        smooth_fact: 0.9,
+        max_measurements: 100_000,
+    }
+}
+
+#[test]
+fn same_seed_reproduces_run() {
+    let mut a = Simulation::new(0.01, 0.2, settings(), 7);
+    let mut b = Simulation::new(0.01, 0.2, settings(), 7);
+    let summary = a.run();
+    assert_eq!(summary, b.run());
+    assert_eq!(a.economy.fortunes(), b.economy.fortunes());
+    assert_eq!(summary.measurements, 22);
+
+    let mut c = Simulation::new(0.01, 0.2, settings(), 8);
+    assert!(c.run().gini != summary.gini);
+}
+
+#[test]
+fn step_with_regulates() {
+    let mut a = Simulation::new(0.0, 0.2, settings(), 3);
+    let mut b = Simulation::new(0.0, 0.2, settings(), 3);
+    for _ in 0..10 {
+        a.step();
+        b.step_with(|economy| economy.solve(0.2, 0.9, 0.0).update);
+    }
+    assert_eq!(a.economy.tax, 0.0);
+    assert!(b.economy.tax > 0.0);
+    assert!(b.economy.gini() < a.economy.gini());
+}
+
+#[test]
+fn run_stops_without_converging() {
+    for &smooth_fact in &[1.0, 2.0, f64::NAN] {
+        let mut settings = settings();
+        settings.smooth_fact = smooth_fact;
+        settings.max_measurements = 20;
+        let summary = Simulation::new(0.01, 0.2, settings, 7).run();
+        assert!(!summary.converged);
+        assert_eq!(summary.measurements, 20);
+    }
+    let summary = Simulation::new(0.01, 0.2, settings(), 7).run();
+    assert!(summary.converged);
+    assert!(summary.measurements < 100_000);
+}
@@ -0,0 +1,142 @@
+extern crate piston_mix_economy;
+extern crate rand;
+
+mod common;
+
+use piston_mix_economy::{Clamp, Economy, InflationBound, Metric, Scratch, SolverStrategy};
+
+fn economy() -> Economy {
+    common::economy(0.25, 0.03, 1.0)
+}
+
+const BISECTION: SolverStrategy = SolverStrategy::Bisection {
+    tolerance: 1e-6,
+    max_iterations: 100,
+};
+
+#[test]
+fn bisection_reaches_target() {
+    let mut economy = economy();
+    let mut scratch = Scratch::new();
+    let high = economy.preview_gini(0.001, &mut scratch);
+    let low = economy.preview_gini(1.0, &mut scratch);
+    let target = 0.5 * (high + low);
+    let report = economy.solve_with(target, BISECTION, 0.001);
+    assert_eq!(report.bracketed, Some(true));
+    assert_eq!(report.clamp, None);
+    assert!(report.converged(1e-4), "{:?}", report);
+    assert!(report.iterations <= 100);
+}
+
+#[test]
+fn bisection_reports_unreachable_target() {
+    let mut economy = economy();
+    let report = economy.solve_with(0.0, BISECTION, 0.001);
+    assert_eq!(report.bracketed, Some(false));
+    assert_eq!(report.clamp, Some(Clamp::MaxTax));
+    assert_eq!(report.tax, 1.0);
+    assert!(!report.converged(1e-4));
+
+    let mut economy = Economy::new(0.0, 0.25, 100);
+    let report = economy.solve_with(0.9, BISECTION, 0.001);
+    assert_eq!(report.bracketed, Some(false));
+    assert_eq!(report.clamp, Some(Clamp::MinTax));
+}
+
+#[test]
+fn solve_other_metrics() {
+    for &metric in &[Metric::TotalSupply, Metric::Mean, Metric::Median, Metric::TopShare(0.1)] {
+        for &strategy in &[BISECTION, SolverStrategy::Search {smooth_target: 0.9}] {
+            let mut economy = economy();
+            let mut scratch = Scratch::new();
+            let a = economy.preview_metric(0.001, metric, &mut scratch);
+            let b = economy.preview_metric(1.0, metric, &mut scratch);
+            let target = 0.5 * (a + b);
+            let report = economy.solve_metric(metric, target, strategy, 0.001);
+            assert_eq!(report.metric, metric);
+            assert_eq!(report.value, economy.metric(metric));
+            assert!(report.error.abs() <= 0.01 * (a - b).abs(), "{:?}", report);
+        }
+    }
+}
+
+#[test]
+fn solve_bounded_inflation() {
+    let unbounded = InflationBound {min_net: -1e9, max_net: 1e9};
+    let mut a = economy();
+    let mut b = economy();
+    let report = a.solve_bounded(0.1, unbounded, BISECTION, 0.001);
+    let expected = b.solve_with(0.1, BISECTION, 0.001);
+    assert!(!report.conflict);
+    assert_eq!(report.solve.tax, expected.tax);
+    assert!(report.solve.update.net > 0.0);
+
+    let mut c = economy();
+    let bound = InflationBound {min_net: 0.0, max_net: 0.5 * report.solve.update.net};
+    let bounded = c.solve_bounded(0.1, bound, BISECTION, 0.001);
+    assert!(bounded.conflict);
+    assert!(bounded.within_bound(), "{:?}", bounded);
+    assert!(bounded.solve.tax < bounded.gini_tax);
+    assert!(bounded.solve.gini > report.solve.gini);
+}
+
+#[test]
+fn solve_is_scale_invariant() {
+    let scale: f64 = 1000.0;
+    let mut a: Economy = Economy::with_soft_limit(0.0, 0.25, 100, 1.0);
+    let mut b = Economy::with_soft_limit(0.0, 0.25 * scale, 100, scale);
+    let (mut rng_a, mut rng_b) = (common::rng(), common::rng());
+    for _ in 0..20 {
+        common::random_transactions(&mut a, &mut rng_a, 0.03, 500);
+        common::random_transactions(&mut b, &mut rng_b, 0.03 * scale, 500);
+        let report_a = a.solve(0.3, 0.9, 0.001);
+        let report_b = b.solve(0.3, 0.9, 0.001);
+        assert!((report_a.tax - report_b.tax).abs() < 1e-9, "{:?} {:?}", report_a, report_b);
+        assert!((a.gini() - b.gini()).abs() < 1e-9);
+
+        let ((min_a, max_a), (min_b, max_b)) = (a.min_max(), b.min_max());
+        assert!((min_a * scale - min_b).abs() < 1e-9 * scale);
+        assert!((max_a * scale - max_b).abs() < 1e-9 * scale);
+        for (&p, &q) in a.fortunes().iter().zip(b.fortunes()) {
+            assert!((p * scale - q).abs() < 1e-9 * scale);
+        }
+    }
+}
+
+#[test]
+fn normalize_round_trip() {
+    let economy: Economy = Economy::with_soft_limit(0.0, 250.0, 1, 1000.0);
+    assert_eq!(economy.normalize(250.0), 0.25);
+    assert_eq!(economy.denormalize(0.25), 250.0);
+    for &x in &[0.0, 1.5, 999.0, 1000.0, 12345.678] {
+        assert!((economy.denormalize(economy.normalize(x)) - x).abs() < 1e-9);
+    }
+
+    let economy: Economy<i64> = Economy::with_soft_limit(0.0, 250, 1, 1000);
+    assert_eq!(economy.normalize(250), 0.25);
+    assert_eq!(economy.normalize(1500), 1.5);
+    for x in -1000..3000 {
+        assert_eq!(economy.denormalize(economy.normalize(x)), x);
+    }
+    // Rounds to the nearest unit.
+    assert_eq!(economy.denormalize(0.0004), 0);
+    assert_eq!(economy.denormalize(0.0006), 1);
+    assert_eq!(economy.denormalize(0.0014), 1);
+    assert_eq!(economy.denormalize(-0.0016), -2);
+}
+
+#[test]
+fn solve_bounded_crossing_next_to_max_tax() {
+    let economy = economy();
+    let mut scratch = Scratch::new();
+    let a = economy.preview_net(1.0 - 1e-6, &mut scratch);
+    let b = economy.preview_net(1.0, &mut scratch);
+    assert!(a < b);
+    let bound = InflationBound {min_net: a + 0.9 * (b - a), max_net: 1e9};
+    let mut economy = economy;
+    let report = economy.solve_bounded(0.9, bound, BISECTION, 0.001);
+    assert!(report.conflict);
+    assert!(report.solve.tax <= 1.0, "{:?}", report);
+    assert_eq!(economy.tax, report.solve.tax);
+    assert!(report.within_bound(), "{:?}", report);
+}
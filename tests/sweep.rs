@@ -0,0 +1,76 @@
+extern crate piston_mix_economy;
+
+use piston_mix_economy::*;
+
+fn base() -> SweepSample {
+    SweepSample {
+        id: 10,
+        seed: 1,
+        tax: 0.0,
+        target_gini: None,
+        smooth_target: 0.9,
+        min_tax: 0.001,
+        start_fortune: 0.25,
+        simulation: SimulationSettings {
+            players: 20,
+            avg_trans: 0.05,
+            trans: 100,
+            periods: 2,
+            smooth_fact: 0.8,
+            max_measurements: 100_000,
+        },
+    }
+}
+
+#[test]
+fn grid_samples_every_combination() {
+    let mut grid = SweepGrid::new(base());
+    grid.taxes = vec![0.01, 0.02, 0.03];
+    grid.target_ginis = vec![None, Some(0.2)];
+    let samples = grid.samples();
+    assert_eq!(samples.len(), 6);
+    assert_eq!(samples[0].id, 10);
+    assert_eq!(samples[5].id, 15);
+    assert_eq!(samples[3].tax, 0.02);
+    assert_eq!(samples[3].target_gini, Some(0.2));
+    assert!(samples.iter().all(|s| s.start_fortune == 0.25 && s.simulation.players == 20));
+}
+
+#[test]
+fn sweep_matches_sequential_runs() {
+    let mut grid = SweepGrid::new(base());
+    grid.taxes = vec![0.0, 0.01, 0.05];
+    grid.target_ginis = vec![None, Some(0.3)];
+    let samples = grid.samples();
+    let results = sweep(&samples, 4);
+    for (sample, result) in samples.iter().zip(&results) {
+        assert_eq!(*result, sample.run());
+    }
+}
+
+#[test]
+fn sweep_output() {
+    let mut sample = base();
+    sample.target_gini = Some(0.3);
+    let results = sweep(&[base(), sample], 0);
+
+    let mut csv = vec![];
+    SweepFormat::Csv.write(&mut csv, &results).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("id,seed,tax,target_gini,"));
+    assert!(lines[0].ends_with(",measurements,converged"));
+    assert!(lines[1].ends_with(",true"));
+    assert!(lines[1].starts_with("10,1,0,,0.9,"));
+    assert!(lines[2].starts_with("10,1,0,0.3,0.9,"));
+
+    let mut json = vec![];
+    SweepFormat::JsonLines.write(&mut json, &results).unwrap();
+    let json = String::from_utf8(json).unwrap();
+    let lines: Vec<&str> = json.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("{\"id\":10,\"seed\":1,\"tax\":0.0,\"target_gini\":null,"));
+    assert!(lines[1].contains("\"target_gini\":0.3,"));
+    assert!(lines[1].ends_with('}'));
+}
@@ -0,0 +1,43 @@
+extern crate piston_mix_economy;
+
+use std::f64;
+
+use piston_mix_economy::{Departure, Economy, TransactionError};
+
+#[test]
+fn rejected_transactions() {
+    let mut economy = Economy::new(0.1, 0.5, 3);
+    let ids: Vec<_> = economy.ids().collect();
+    assert_eq!(economy.transaction(ids[0], ids[0], 0.1), Err(TransactionError::SamePlayer));
+    for &amount in &[-0.1, f64::INFINITY, f64::NEG_INFINITY] {
+        assert_eq!(economy.transaction(ids[0], ids[1], amount),
+            Err(TransactionError::InvalidAmount(amount)));
+    }
+    match economy.transaction(ids[0], ids[1], f64::NAN) {
+        Err(TransactionError::InvalidAmount(amount)) => assert!(amount.is_nan()),
+        x => panic!("{:?}", x),
+    }
+    assert_eq!(economy.transaction(ids[0], ids[1], 0.5), Err(TransactionError::InsufficientFunds));
+    economy.remove_player(ids[2], Departure::Burn);
+    assert_eq!(economy.transaction(ids[0], ids[2], 0.1),
+        Err(TransactionError::UnknownPlayer(ids[2])));
+    assert_eq!(economy.transaction(ids[2], ids[0], 0.1),
+        Err(TransactionError::UnknownPlayer(ids[2])));
+    assert_eq!(economy.fortunes(), &[0.5, 0.5]);
+}
+
+#[test]
+fn insufficient_funds_boundary() {
+    let mut economy: Economy<i64> = Economy::with_soft_limit(0.1, 500, 2, 1000);
+    let ids: Vec<_> = economy.ids().collect();
+    // A player must keep a positive fortune.
+    assert_eq!(economy.check_transaction(ids[0], ids[1], 500),
+        Err(TransactionError::InsufficientFunds));
+    assert_eq!(economy.check_transaction(ids[0], ids[1], 499), Ok(()));
+    assert_eq!(economy.transaction(ids[0], ids[1], 500), Err(TransactionError::InsufficientFunds));
+    assert_eq!(economy.fortunes(), &[500, 500]);
+    economy.transaction(ids[0], ids[1], 499).unwrap();
+    assert_eq!(economy.fortunes(), &[1, 999]);
+    assert_eq!(economy.transaction(ids[0], ids[1], 1), Err(TransactionError::InsufficientFunds));
+    economy.transaction(ids[0], ids[1], 0).unwrap();
+}
@@ -0,0 +1,44 @@
+extern crate piston_mix_economy;
+
+use piston_mix_economy::{Departure, Economy, Metric, Purpose, TransactionError};
+
+#[test]
+fn treasury_collects_burns_and_departures() {
+    let mut economy: Economy<i64> = Economy::with_soft_limit(0.5, 600, 3, 1000);
+    economy.collect_burns = true;
+    let ids: Vec<_> = economy.ids().collect();
+    economy.transaction(ids[0], ids[1], 500).unwrap();
+
+    let before = economy.metric(Metric::TotalSupply);
+    let report = economy.update();
+    assert!(report.burned > 0.0);
+    assert_eq!(report.collected, report.burned);
+    assert_eq!(economy.treasury.collected_burns as f64, report.burned);
+    let after = economy.metric(Metric::TotalSupply) + economy.treasury.balance as f64;
+    assert_eq!(after, before + report.distributed);
+
+    let fortune = economy.remove_player(ids[2], Departure::Treasury).unwrap();
+    assert_eq!(economy.treasury.collected_departures, fortune);
+}
+
+#[test]
+fn treasury_spending() {
+    let mut economy: Economy<i64> = Economy::with_soft_limit(0.5, 600, 2, 1000);
+    let ids: Vec<_> = economy.ids().collect();
+    economy.remove_player(ids[1], Departure::Treasury).unwrap();
+    assert_eq!(economy.treasury.balance, 600);
+
+    economy.pay_from_treasury(ids[0], 100, Purpose::QuestReward).unwrap();
+    assert_eq!(economy.fortune(ids[0]), Some(700));
+    economy.spend_from_treasury(200, Purpose::NpcPurchase).unwrap();
+    assert_eq!(economy.treasury.balance, 300);
+    assert_eq!(economy.treasury.spent(Purpose::QuestReward), 100);
+    assert_eq!(economy.treasury.spent(Purpose::NpcPurchase), 200);
+    assert_eq!(economy.treasury.total_spent(), 300);
+
+    assert_eq!(economy.spend_from_treasury(301, Purpose::Event),
+        Err(TransactionError::InsufficientFunds));
+    assert_eq!(economy.pay_from_treasury(ids[1], 1, Purpose::Event),
+        Err(TransactionError::UnknownPlayer(ids[1])));
+    assert_eq!(economy.treasury.balance, 300);
+}